@@ -1,5 +1,3 @@
 #!/bin/sh
 
-diesel migration run
-
 docker build -t showroom-api:latest .
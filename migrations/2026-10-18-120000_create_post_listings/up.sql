@@ -0,0 +1,13 @@
+CREATE TABLE post_listings
+(
+    post_id       UUID PRIMARY KEY,
+    listing_id    UUID NOT NULL,
+
+    -- Metadata
+    migrated_at   TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    migrated_by   VARCHAR NOT NULL,
+
+    -- Foreign keys
+    FOREIGN KEY (post_id) REFERENCES posts(id) ON DELETE CASCADE,
+    FOREIGN KEY (listing_id) REFERENCES listings(id) ON DELETE CASCADE
+);
@@ -0,0 +1,7 @@
+ALTER TABLE posts
+    DROP COLUMN created_at,
+    DROP COLUMN updated_at,
+    DROP COLUMN deleted_at,
+    DROP COLUMN created_by,
+    DROP COLUMN updated_by,
+    DROP COLUMN deleted_by;
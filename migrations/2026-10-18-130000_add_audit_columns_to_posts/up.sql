@@ -0,0 +1,12 @@
+ALTER TABLE posts
+    ADD COLUMN created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    ADD COLUMN updated_at TIMESTAMPTZ DEFAULT NULL,
+    ADD COLUMN deleted_at TIMESTAMPTZ DEFAULT NULL,
+    ADD COLUMN created_by VARCHAR,
+    ADD COLUMN updated_by VARCHAR DEFAULT NULL,
+    ADD COLUMN deleted_by VARCHAR DEFAULT NULL;
+
+-- Existing posts were created by their authors.
+UPDATE posts SET created_by = author;
+
+ALTER TABLE posts ALTER COLUMN created_by SET NOT NULL;
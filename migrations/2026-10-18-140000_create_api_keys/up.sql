@@ -0,0 +1,20 @@
+CREATE TABLE api_keys
+(
+    id            UUID PRIMARY KEY,
+    name          VARCHAR NOT NULL,
+    owner         VARCHAR NOT NULL,
+    key_prefix    VARCHAR NOT NULL,
+    key_hash      VARCHAR NOT NULL UNIQUE,
+    scopes        VARCHAR[] NOT NULL,
+    expires_at    TIMESTAMPTZ DEFAULT NULL,
+    last_used_at  TIMESTAMPTZ DEFAULT NULL,
+
+    -- Metadata
+    created_at    TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    updated_at    TIMESTAMPTZ DEFAULT NULL,
+    revoked_at    TIMESTAMPTZ DEFAULT NULL,
+
+    created_by    VARCHAR NOT NULL,
+    updated_by    VARCHAR DEFAULT NULL,
+    revoked_by    VARCHAR DEFAULT NULL
+);
@@ -0,0 +1,105 @@
+use std::sync::Arc;
+
+use axum::extract::{Request, State};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::PgConnection;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+use crate::auth::principal::Principal;
+use crate::error::app_error::AppError;
+use crate::service::api_key_service;
+use crate::utils::blocking::run_blocking;
+
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+const KEY_PREFIX: &str = "sk_";
+const KEY_BYTES: usize = 32;
+const DISPLAYED_PREFIX_LENGTH: usize = 8;
+
+pub struct GeneratedKey {
+    pub key: String,
+    /// The first characters of the key, kept so a key can be told apart without revealing it.
+    pub prefix: String,
+    pub hash: String,
+}
+
+pub fn generate_key() -> GeneratedKey {
+    let mut bytes = [0u8; KEY_BYTES];
+    OsRng.fill_bytes(&mut bytes);
+
+    let key = format!("{}{}", KEY_PREFIX, URL_SAFE_NO_PAD.encode(bytes));
+    GeneratedKey {
+        prefix: key[..KEY_PREFIX.len() + DISPLAYED_PREFIX_LENGTH].to_string(),
+        hash: hash_key(&key),
+        key,
+    }
+}
+
+/// Keys are random enough for a plain SHA-256, unlike passwords they cannot be brute forced.
+pub fn hash_key(key: &str) -> String {
+    format!("{:x}", Sha256::digest(key.as_bytes()))
+}
+
+/// Authenticates requests carrying an `X-Api-Key` header, requests without one pass through.
+pub async fn authenticate_api_key(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let Some(api_key) = request.headers().get(API_KEY_HEADER) else {
+        return next.run(request).await;
+    };
+
+    if request.extensions().get::<Principal>().is_some() {
+        return AppError::Unauthorized(String::from(
+            "Use either a bearer token or an API key, not both",
+        ))
+        .into_response();
+    }
+
+    let Ok(api_key) = api_key.to_str().map(str::to_string) else {
+        return AppError::Unauthorized(String::from("Invalid, expired or revoked API key"))
+            .into_response();
+    };
+
+    match run_blocking(move || api_key_service::authenticate(pool, &api_key)).await {
+        Ok(principal) => {
+            request.extensions_mut().insert(principal);
+            next.run(request).await
+        }
+        Err(err) => err.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_keys_are_unique() {
+        // Given 'two generated keys'
+        let first = generate_key();
+        let second = generate_key();
+
+        // Then 'they should differ'
+        assert_ne!(first.key, second.key);
+        assert_ne!(first.hash, second.hash);
+    }
+
+    #[test]
+    fn generated_key_matches_its_hash_and_prefix() {
+        // Given 'a generated key'
+        let generated = generate_key();
+
+        // Then 'the hash and the prefix should be derived from the key'
+        assert_eq!(generated.hash, hash_key(&generated.key));
+        assert!(generated.key.starts_with(&generated.prefix));
+        assert_eq!(generated.prefix.len(), 11);
+    }
+}
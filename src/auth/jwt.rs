@@ -0,0 +1,220 @@
+use std::error::Error;
+use std::fs;
+use std::sync::Arc;
+
+use axum::extract::{Request, State};
+use axum::http::header::AUTHORIZATION;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
+use log::{error, info, warn};
+use serde::Deserialize;
+
+use crate::auth::principal::Principal;
+use crate::auth::role::Role;
+use crate::config::config::AuthConfig;
+use crate::error::app_error::AppError;
+
+#[derive(Deserialize, Debug)]
+struct Claims {
+    sub: String,
+    #[serde(default)]
+    roles: Vec<String>,
+}
+
+/// Validates bearer tokens signed with HS256 (shared secret) or RS256 (public key).
+pub struct JwtVerifier {
+    key: DecodingKey,
+    validation: Validation,
+}
+
+impl JwtVerifier {
+    pub fn new(key: DecodingKey, algorithm: Algorithm) -> JwtVerifier {
+        JwtVerifier {
+            key,
+            validation: Validation::new(algorithm),
+        }
+    }
+
+    pub fn with_issuer(mut self, issuer: &str) -> JwtVerifier {
+        self.validation.set_issuer(&[issuer]);
+        self
+    }
+
+    pub fn with_audience(mut self, audience: &str) -> JwtVerifier {
+        self.validation.set_audience(&[audience]);
+        self
+    }
+
+    /// Builds the verifier from the validated auth settings, reading the RS256 public key file.
+    pub fn from_config(config: &AuthConfig) -> Result<JwtVerifier, Box<dyn Error>> {
+        info!("Verifying bearer tokens with {}", config.jwt_algorithm);
+
+        let mut verifier = match (
+            config.jwt_algorithm.as_str(),
+            &config.jwt_secret,
+            &config.jwt_public_key_file,
+        ) {
+            ("HS256", Some(secret), _) => JwtVerifier::new(
+                DecodingKey::from_secret(secret.as_bytes()),
+                Algorithm::HS256,
+            ),
+            ("RS256", _, Some(key_file)) => {
+                let key = DecodingKey::from_rsa_pem(&fs::read(key_file).map_err(|err| {
+                    error!("Unable to read '{}', error: {}", key_file, err);
+                    err
+                })?)?;
+                JwtVerifier::new(key, Algorithm::RS256)
+            }
+            (other, _, _) => {
+                return Err(format!("Unsupported JWT configuration for '{}'", other).into())
+            }
+        };
+
+        if let Some(issuer) = &config.jwt_issuer {
+            verifier = verifier.with_issuer(issuer);
+        }
+        if let Some(audience) = &config.jwt_audience {
+            verifier = verifier.with_audience(audience);
+        } else {
+            verifier.validation.validate_aud = false;
+        }
+
+        Ok(verifier)
+    }
+
+    pub fn verify(&self, token: &str) -> Result<Principal, AppError> {
+        let token_data = decode::<Claims>(token, &self.key, &self.validation).map_err(|err| {
+            warn!("Rejected bearer token, error: {}", err);
+            AppError::Unauthorized(String::from("Invalid or expired bearer token"))
+        })?;
+
+        let roles = token_data
+            .claims
+            .roles
+            .iter()
+            .filter_map(|role| match role.parse::<Role>() {
+                Ok(role) => Some(role),
+                Err(err) => {
+                    warn!(
+                        "Ignoring role of '{}', error: {}",
+                        token_data.claims.sub, err
+                    );
+                    None
+                }
+            })
+            .collect();
+
+        Ok(Principal {
+            subject: token_data.claims.sub,
+            roles,
+        })
+    }
+}
+
+/// Authenticates requests carrying an `Authorization: Bearer` header, anonymous requests pass through.
+pub async fn authenticate(
+    State(verifier): State<Arc<JwtVerifier>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let Some(authorization) = request.headers().get(AUTHORIZATION) else {
+        return next.run(request).await;
+    };
+
+    let token = match authorization
+        .to_str()
+        .ok()
+        .and_then(|value| value.strip_prefix("Bearer "))
+    {
+        Some(token) => token.trim(),
+        None => {
+            return AppError::Unauthorized(String::from("Expected a bearer token")).into_response()
+        }
+    };
+
+    match verifier.verify(token) {
+        Ok(principal) => {
+            request.extensions_mut().insert(principal);
+            next.run(request).await
+        }
+        Err(err) => err.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use serde::Serialize;
+
+    #[derive(Serialize)]
+    struct TestClaims {
+        sub: String,
+        exp: i64,
+        roles: Vec<String>,
+    }
+
+    fn token(secret: &str, exp: i64) -> String {
+        let claims = TestClaims {
+            sub: String::from("jane"),
+            exp,
+            roles: vec![String::from("seller")],
+        };
+        encode(
+            &Header::new(Algorithm::HS256),
+            &claims,
+            &EncodingKey::from_secret(secret.as_bytes()),
+        )
+        .unwrap()
+    }
+
+    fn verifier(secret: &str) -> JwtVerifier {
+        JwtVerifier::new(
+            DecodingKey::from_secret(secret.as_bytes()),
+            Algorithm::HS256,
+        )
+    }
+
+    #[test]
+    fn valid_token_is_accepted() {
+        // Given 'a token signed with the configured secret'
+        let token = token("secret", chrono::Utc::now().timestamp() + 60);
+
+        // When 'the token is verified'
+        let principal = verifier("secret").verify(&token);
+
+        // Then 'the caller should be identified'
+        assert_eq!(
+            principal,
+            Ok(Principal {
+                subject: String::from("jane"),
+                roles: vec![Role::Seller],
+            })
+        );
+    }
+
+    #[test]
+    fn expired_token_is_rejected() {
+        // Given 'a token that expired an hour ago'
+        let token = token("secret", chrono::Utc::now().timestamp() - 3600);
+
+        // When 'the token is verified'
+        let principal = verifier("secret").verify(&token);
+
+        // Then 'the caller should be unauthorized'
+        assert!(matches!(principal, Err(AppError::Unauthorized(_))));
+    }
+
+    #[test]
+    fn token_signed_with_another_secret_is_rejected() {
+        // Given 'a token signed with another secret'
+        let token = token("another", chrono::Utc::now().timestamp() + 60);
+
+        // When 'the token is verified'
+        let principal = verifier("secret").verify(&token);
+
+        // Then 'the caller should be unauthorized'
+        assert!(matches!(principal, Err(AppError::Unauthorized(_))));
+    }
+}
@@ -0,0 +1,4 @@
+pub mod api_key;
+pub mod jwt;
+pub mod principal;
+pub mod role;
@@ -0,0 +1,45 @@
+use axum::async_trait;
+use axum::extract::FromRequestParts;
+use axum::http::request::Parts;
+
+use crate::auth::role::Role;
+use crate::error::app_error::AppError;
+
+/// The authenticated caller, put in the request extensions by the `authenticate` middleware.
+///
+/// Extracting it rejects anonymous requests with 401, use `Option<Principal>` where a caller is optional.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Principal {
+    pub subject: String,
+    pub roles: Vec<Role>,
+}
+
+impl Principal {
+    /// The name recorded in the `created_by`, `updated_by` and `deleted_by` audit columns.
+    pub fn audit_name(&self) -> String {
+        self.subject.clone()
+    }
+
+    /// Admins are granted every role.
+    pub fn has_role(&self, role: Role) -> bool {
+        self.roles
+            .iter()
+            .any(|granted| *granted == role || *granted == Role::Admin)
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for Principal
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<Principal>()
+            .cloned()
+            .ok_or_else(|| AppError::Unauthorized(String::from("Authentication is required")))
+    }
+}
@@ -0,0 +1,105 @@
+use std::str::FromStr;
+
+use axum::extract::Request;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+
+use crate::auth::principal::Principal;
+use crate::error::app_error::AppError;
+
+/// Roles granted through the `roles` claim, anonymous callers being `Public`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Role {
+    Admin,
+    CatalogEditor,
+    Seller,
+    Public,
+}
+
+impl FromStr for Role {
+    type Err = String;
+
+    fn from_str(role: &str) -> Result<Self, Self::Err> {
+        match role {
+            "admin" => Ok(Role::Admin),
+            "catalog-editor" => Ok(Role::CatalogEditor),
+            "seller" => Ok(Role::Seller),
+            "public" => Ok(Role::Public),
+            other => Err(format!("Unknown role: '{}'", other)),
+        }
+    }
+}
+
+/// Rejects anonymous callers with 401 and callers lacking every one of `roles` with 403.
+pub fn authorize(principal: Option<&Principal>, roles: &[Role]) -> Result<(), AppError> {
+    match principal {
+        None => Err(AppError::Unauthorized(String::from(
+            "Authentication is required",
+        ))),
+        Some(principal) if roles.iter().any(|role| principal.has_role(*role)) => Ok(()),
+        Some(_) => Err(AppError::Forbidden(String::from(
+            "Insufficient role for this operation",
+        ))),
+    }
+}
+
+async fn require(request: Request, next: Next, roles: &[Role]) -> Response {
+    match authorize(request.extensions().get::<Principal>(), roles) {
+        Ok(()) => next.run(request).await,
+        Err(err) => err.into_response(),
+    }
+}
+
+pub async fn require_admin(request: Request, next: Next) -> Response {
+    require(request, next, &[Role::Admin]).await
+}
+
+pub async fn require_catalog_editor(request: Request, next: Next) -> Response {
+    require(request, next, &[Role::CatalogEditor]).await
+}
+
+pub async fn require_seller(request: Request, next: Next) -> Response {
+    require(request, next, &[Role::Seller]).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn principal(roles: Vec<Role>) -> Principal {
+        Principal {
+            subject: String::from("jane"),
+            roles,
+        }
+    }
+
+    #[test]
+    fn anonymous_caller_is_unauthorized() {
+        assert!(matches!(
+            authorize(None, &[Role::Seller]),
+            Err(AppError::Unauthorized(_))
+        ));
+    }
+
+    #[test]
+    fn caller_without_role_is_forbidden() {
+        // Given 'a seller'
+        let seller = principal(vec![Role::Seller]);
+
+        // When 'the seller edits the catalog'
+        let result = authorize(Some(&seller), &[Role::CatalogEditor]);
+
+        // Then 'the seller should be forbidden'
+        assert!(matches!(result, Err(AppError::Forbidden(_))));
+    }
+
+    #[test]
+    fn admin_is_granted_every_role() {
+        // Given 'an admin'
+        let admin = principal(vec![Role::Admin]);
+
+        // Then 'the admin should be allowed everywhere'
+        assert!(authorize(Some(&admin), &[Role::CatalogEditor]).is_ok());
+        assert!(authorize(Some(&admin), &[Role::Seller]).is_ok());
+    }
+}
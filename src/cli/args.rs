@@ -0,0 +1,180 @@
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use uuid::Uuid;
+
+/// Showroom API server and management commands.
+#[derive(Parser, Debug)]
+#[command(name = "showroom-api", version)]
+pub struct Cli {
+    /// Name recorded in the audit columns by management commands.
+    #[arg(long = "as", global = true, default_value = "cli")]
+    pub operator: String,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug, PartialEq)]
+pub enum Command {
+    /// Serve the HTTP API, the default when no command is given.
+    Serve,
+    /// Apply, revert or list the embedded migrations.
+    #[command(subcommand)]
+    Migrate(MigrateCommand),
+    /// Insert the brands, models, versions, cars, sellers and posts of a JSON file.
+    Seed {
+        #[arg(long)]
+        file: PathBuf,
+    },
+    /// Write every record of a resource to stdout or a file.
+    #[command(subcommand)]
+    Export(ExportCommand),
+    /// Issue an API key and print it, it cannot be read again afterwards.
+    CreateApiKey(CreateApiKeyArgs),
+    /// Migrate the posts not yet migrated into cars and listings and print the report.
+    MigratePosts(MigratePostsArgs),
+}
+
+#[derive(Subcommand, Debug, PartialEq)]
+pub enum MigrateCommand {
+    /// Apply every pending migration.
+    Up,
+    /// Revert the most recent migrations.
+    Down {
+        #[arg(long, default_value_t = 1)]
+        steps: u32,
+    },
+    /// List applied, pending and unknown migrations.
+    Status,
+}
+
+#[derive(Subcommand, Debug, PartialEq)]
+pub enum ExportCommand {
+    /// Published posts.
+    Posts(ExportArgs),
+}
+
+#[derive(Args, Debug, PartialEq)]
+pub struct ExportArgs {
+    #[arg(long, value_enum, default_value_t = ExportFormat::Csv)]
+    pub format: ExportFormat,
+    /// File to write, stdout when omitted.
+    #[arg(long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+#[derive(Args, Debug, PartialEq)]
+pub struct CreateApiKeyArgs {
+    #[arg(long)]
+    pub name: String,
+    #[arg(long)]
+    pub owner: String,
+    /// Role granted to the key, repeat for several.
+    #[arg(long = "scope", required = true)]
+    pub scopes: Vec<String>,
+    /// RFC 3339 timestamp after which the key is rejected.
+    #[arg(long)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Args, Debug, PartialEq)]
+pub struct MigratePostsArgs {
+    /// Roll every post back, only reporting what would be migrated.
+    #[arg(long)]
+    pub dry_run: bool,
+    /// Seller of the posts whose author matches no seller.
+    #[arg(long)]
+    pub default_seller_id: Option<Uuid>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn cli_definition_is_valid() {
+        // Given 'the command line definition'
+        let command = Cli::command();
+
+        // When 'clap checks it'
+        // Then 'it should not panic'
+        command.debug_assert();
+    }
+
+    #[test]
+    fn no_command_serves() {
+        // Given 'no arguments'
+        let args = ["showroom-api"];
+
+        // When 'the arguments are parsed'
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        // Then 'there should be no command, which serves the API'
+        assert_eq!(cli.command, None);
+        assert_eq!(cli.operator, "cli");
+    }
+
+    #[test]
+    fn export_posts_as_csv() {
+        // Given 'an export of posts as csv'
+        let args = ["showroom-api", "export", "posts", "--format", "csv"];
+
+        // When 'the arguments are parsed'
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        // Then 'the export should write csv to stdout'
+        assert_eq!(
+            cli.command,
+            Some(Command::Export(ExportCommand::Posts(ExportArgs {
+                format: ExportFormat::Csv,
+                output: None,
+            })))
+        );
+    }
+
+    #[test]
+    fn create_api_key_requires_a_scope() {
+        // Given 'an API key without scopes'
+        let args = [
+            "showroom-api",
+            "create-api-key",
+            "--name",
+            "ci",
+            "--owner",
+            "ops",
+        ];
+
+        // When 'the arguments are parsed'
+        let cli = Cli::try_parse_from(args);
+
+        // Then 'the arguments should be rejected'
+        assert!(cli.is_err());
+    }
+
+    #[test]
+    fn migrate_posts_dry_run() {
+        // Given 'a dry run of the posts migration'
+        let args = ["showroom-api", "migrate-posts", "--dry-run"];
+
+        // When 'the arguments are parsed'
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        // Then 'the migration should be rolled back, without a default seller'
+        assert_eq!(
+            cli.command,
+            Some(Command::MigratePosts(MigratePostsArgs {
+                dry_run: true,
+                default_seller_id: None,
+            }))
+        );
+    }
+}
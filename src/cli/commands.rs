@@ -0,0 +1,204 @@
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::PgConnection;
+use log::info;
+use serde::Deserialize;
+use validator::Validate;
+
+use crate::auth::principal::Principal;
+use crate::auth::role::Role;
+use crate::cli::args::{
+    CreateApiKeyArgs, ExportArgs, ExportCommand, ExportFormat, MigrateCommand, MigratePostsArgs,
+};
+use crate::config::config::{AppConfig, MigrationMode};
+use crate::database::database::{checkout, get_connection_pool};
+use crate::database::migration::{
+    migration_status, prepare_database, revert_migrations, unknown_migrations,
+};
+use crate::error::app_error::AppError;
+use crate::models::api_key_models::IssueApiKey;
+use crate::models::brand_models::CreateBrand;
+use crate::models::car_models::CreateCar;
+use crate::models::model_models::CreateModel;
+use crate::models::post_models::CreatePost;
+use crate::models::seller_models::CreateSeller;
+use crate::models::version_models::CreateVersion;
+use crate::service::{
+    api_key_service, brand_service, car_service, model_service, post_migration_service,
+    post_service, seller_service, version_service,
+};
+use crate::utils::validated_json::field_errors;
+
+type CommandResult = Result<(), Box<dyn Error + Send + Sync>>;
+
+/// Records to insert with `seed`, each list created in the order of the fields.
+#[derive(Deserialize, Validate, Debug, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct SeedFile {
+    #[validate(nested)]
+    pub brands: Vec<CreateBrand>,
+    #[validate(nested)]
+    pub models: Vec<CreateModel>,
+    #[validate(nested)]
+    pub versions: Vec<CreateVersion>,
+    #[validate(nested)]
+    pub cars: Vec<CreateCar>,
+    #[validate(nested)]
+    pub sellers: Vec<CreateSeller>,
+    #[validate(nested)]
+    pub posts: Vec<CreatePost>,
+}
+
+/// Management commands act as an admin, recorded in the audit columns under `operator`.
+fn operator_principal(operator: &str) -> Principal {
+    Principal {
+        subject: operator.to_string(),
+        roles: vec![Role::Admin],
+    }
+}
+
+fn connection_pool(
+    config: &AppConfig,
+) -> Result<Arc<Pool<ConnectionManager<PgConnection>>>, Box<dyn Error + Send + Sync>> {
+    let pool = get_connection_pool(&config.database).map_err(|err| err.to_string())?;
+    Ok(Arc::new(pool))
+}
+
+pub fn migrate(config: &AppConfig, command: MigrateCommand) -> CommandResult {
+    let mut out = io::stdout().lock();
+    let pool = connection_pool(config)?;
+    let mut connection = checkout(&pool, "migration")?;
+
+    match command {
+        MigrateCommand::Up => prepare_database(&mut connection, MigrationMode::Apply)?,
+        MigrateCommand::Down { steps } => {
+            for version in revert_migrations(&mut connection, steps)? {
+                writeln!(out, "Reverted {}", version)?;
+            }
+        }
+        MigrateCommand::Status => {
+            for (name, applied) in migration_status(&mut connection)? {
+                writeln!(out, "[{}] {}", if applied { "X" } else { " " }, name)?;
+            }
+            for version in unknown_migrations(&mut connection)? {
+                writeln!(out, "[?] {} (unknown to this binary)", version)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn seed(config: &AppConfig, file: &Path, operator: &str) -> CommandResult {
+    let mut out = io::stdout().lock();
+    info!("Seed database from '{}'", file.display());
+
+    let seed_file: SeedFile = serde_json::from_str(&fs::read_to_string(file)?)?;
+    seed_file
+        .validate()
+        .map_err(|errors| AppError::Validation(field_errors(&errors)))?;
+
+    let pool = connection_pool(config)?;
+    let principal = operator_principal(operator);
+
+    if !seed_file.brands.is_empty() {
+        let created = brand_service::create_brands(pool.clone(), seed_file.brands, &principal)?;
+        writeln!(out, "Created {} brand(s)", created.len())?;
+    }
+    if !seed_file.models.is_empty() {
+        let created = model_service::create_models(pool.clone(), seed_file.models, &principal)?;
+        writeln!(out, "Created {} model(s)", created.len())?;
+    }
+    if !seed_file.versions.is_empty() {
+        let created =
+            version_service::create_versions(pool.clone(), seed_file.versions, &principal)?;
+        writeln!(out, "Created {} version(s)", created.len())?;
+    }
+    if !seed_file.cars.is_empty() {
+        let created = car_service::create_cars(pool.clone(), seed_file.cars, &principal)?;
+        writeln!(out, "Created {} car(s)", created.len())?;
+    }
+    if !seed_file.sellers.is_empty() {
+        let created = seller_service::create_sellers(pool.clone(), seed_file.sellers, &principal)?;
+        writeln!(out, "Created {} seller(s)", created.len())?;
+    }
+    if !seed_file.posts.is_empty() {
+        let created = post_service::create_posts(pool, seed_file.posts, &principal)?;
+        writeln!(out, "Created {} post(s)", created.len())?;
+    }
+
+    Ok(())
+}
+
+pub fn export(config: &AppConfig, command: ExportCommand) -> CommandResult {
+    let pool = connection_pool(config)?;
+
+    match command {
+        ExportCommand::Posts(ExportArgs { format, output }) => {
+            let post_list = post_service::get_published_posts(pool)?;
+            let writer: Box<dyn Write> = match &output {
+                Some(output) => Box::new(File::create(output)?),
+                None => Box::new(io::stdout().lock()),
+            };
+
+            match format {
+                ExportFormat::Csv => {
+                    let mut writer = csv::Writer::from_writer(writer);
+                    for post in &post_list {
+                        writer.serialize(post)?;
+                    }
+                    writer.flush()?;
+                }
+                ExportFormat::Json => {
+                    let mut writer = writer;
+                    serde_json::to_writer_pretty(&mut writer, &post_list)?;
+                    writeln!(writer)?;
+                }
+            }
+            info!("Exported {} post(s)", post_list.len());
+        }
+    }
+
+    Ok(())
+}
+
+pub fn create_api_key(config: &AppConfig, args: CreateApiKeyArgs, operator: &str) -> CommandResult {
+    let mut out = io::stdout().lock();
+    let issue_api_key = IssueApiKey {
+        name: args.name,
+        owner: args.owner,
+        scopes: args.scopes,
+        expires_at: args.expires_at,
+    };
+    issue_api_key
+        .validate()
+        .map_err(|errors| AppError::Validation(field_errors(&errors)))?;
+
+    let pool = connection_pool(config)?;
+    let issued =
+        api_key_service::issue_api_key(pool, issue_api_key, &operator_principal(operator))?;
+    writeln!(out, "{}", serde_json::to_string_pretty(&issued)?)?;
+
+    Ok(())
+}
+
+/// Unlike the admin endpoint, not bound by the request timeout, for tables too large to migrate
+/// within one request.
+pub fn migrate_posts(config: &AppConfig, args: MigratePostsArgs, operator: &str) -> CommandResult {
+    let mut out = io::stdout().lock();
+    let pool = connection_pool(config)?;
+    let report = post_migration_service::migrate_posts(
+        pool,
+        args.dry_run,
+        args.default_seller_id,
+        &operator_principal(operator),
+    )?;
+    writeln!(out, "{}", serde_json::to_string_pretty(&report)?)?;
+
+    Ok(())
+}
@@ -0,0 +1,680 @@
+use std::env;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use axum::http::HeaderValue;
+use serde::Deserialize;
+use toml::{Table, Value};
+
+pub const CONFIG_FILE_KEY: &str = "APP_CONFIG_FILE";
+const DEFAULT_CONFIG_FILE: &str = "config.toml";
+
+/// Application settings, read from defaults, then `config.toml` (or the file at `APP_CONFIG_FILE`)
+/// with the `[profile.<APP_ENV>]` table applied on top, then environment variables.
+#[derive(Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct AppConfig {
+    #[serde(skip)]
+    pub environment: Environment,
+    pub server: ServerConfig,
+    pub database: DatabaseConfig,
+    pub log: LogConfig,
+    pub tracing: TracingConfig,
+    pub auth: AuthConfig,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Environment {
+    #[default]
+    Development,
+    Test,
+    Production,
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    pub host: String,
+    pub port: u16,
+    /// Maximum request body size, in bytes.
+    pub body_limit: usize,
+    /// Seconds before a request is answered with 408.
+    pub request_timeout: u64,
+    /// Allowed CORS origins, `*` allowing any origin.
+    pub cors_origins: Vec<String>,
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseConfig {
+    pub url: String,
+    pub pool_size: u32,
+    pub min_idle: Option<u32>,
+    /// Seconds to wait for a pooled connection.
+    pub connection_timeout: u64,
+    /// Seconds before a connection is closed and replaced, 0 keeping connections forever.
+    pub max_lifetime: u64,
+    /// Seconds before an idle connection above `min_idle` is closed, 0 keeping it open.
+    pub idle_timeout: u64,
+    /// Whether connections are checked with a query before being handed out.
+    pub test_on_checkout: bool,
+    /// What to do with pending embedded migrations at startup.
+    pub migrations: MigrationMode,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum MigrationMode {
+    /// Leave the schema alone.
+    #[default]
+    Off,
+    /// Log the pending migrations without applying them.
+    DryRun,
+    /// Apply the pending migrations.
+    Apply,
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct LogConfig {
+    /// A `RUST_LOG` style filter, e.g. `info` or `showroom_api=debug`.
+    pub level: String,
+    pub format: LogFormat,
+}
+
+/// `Text` for humans, `Json` lines carrying the request id and route for the log pipeline.
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct TracingConfig {
+    /// Whether spans are exported over OTLP.
+    pub enabled: bool,
+    /// OTLP gRPC endpoint of the collector.
+    pub otlp_endpoint: String,
+    pub service_name: String,
+    /// Share of root spans sampled, from 0 to 1.
+    pub sample_ratio: f64,
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuthConfig {
+    pub jwt_algorithm: String,
+    pub jwt_secret: Option<String>,
+    pub jwt_public_key_file: Option<String>,
+    pub jwt_issuer: Option<String>,
+    pub jwt_audience: Option<String>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
+            host: String::from("0.0.0.0"),
+            port: 3000,
+            body_limit: 2 * 1024 * 1024,
+            request_timeout: 30,
+            cors_origins: vec![String::from("*")],
+        }
+    }
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        DatabaseConfig {
+            url: String::new(),
+            pool_size: 10,
+            min_idle: None,
+            connection_timeout: 30,
+            max_lifetime: 30 * 60,
+            idle_timeout: 10 * 60,
+            test_on_checkout: true,
+            migrations: MigrationMode::Off,
+        }
+    }
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        LogConfig {
+            level: String::from("info"),
+            format: LogFormat::Text,
+        }
+    }
+}
+
+impl Default for TracingConfig {
+    fn default() -> Self {
+        TracingConfig {
+            enabled: false,
+            otlp_endpoint: String::from("http://localhost:4317"),
+            service_name: String::from("showroom-api"),
+            sample_ratio: 1.0,
+        }
+    }
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        AuthConfig {
+            jwt_algorithm: String::from("HS256"),
+            jwt_secret: None,
+            jwt_public_key_file: None,
+            jwt_issuer: None,
+            jwt_audience: None,
+        }
+    }
+}
+
+impl Environment {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Environment::Development => "development",
+            Environment::Test => "test",
+            Environment::Production => "production",
+        }
+    }
+}
+
+impl FromStr for Environment {
+    type Err = String;
+
+    fn from_str(environment: &str) -> Result<Self, Self::Err> {
+        match environment {
+            "development" => Ok(Environment::Development),
+            "test" => Ok(Environment::Test),
+            "production" => Ok(Environment::Production),
+            other => Err(format!(
+                "expected development, test or production, got '{}'",
+                other
+            )),
+        }
+    }
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            other => Err(format!("expected text or json, got '{}'", other)),
+        }
+    }
+}
+
+impl FromStr for MigrationMode {
+    type Err = String;
+
+    fn from_str(mode: &str) -> Result<Self, Self::Err> {
+        match mode {
+            "off" => Ok(MigrationMode::Off),
+            "dry-run" => Ok(MigrationMode::DryRun),
+            "apply" => Ok(MigrationMode::Apply),
+            other => Err(format!("expected off, dry-run or apply, got '{}'", other)),
+        }
+    }
+}
+
+/// Every problem found in the configuration, so they can all be fixed at once.
+#[derive(Debug, PartialEq)]
+pub struct ConfigError(pub Vec<String>);
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid configuration:")?;
+        for error in &self.0 {
+            write!(f, "\n  - {}", error)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl AppConfig {
+    pub fn load() -> Result<AppConfig, ConfigError> {
+        let file = match env::var(CONFIG_FILE_KEY) {
+            Ok(file) => Some(read_file(&file)?),
+            Err(_) if Path::new(DEFAULT_CONFIG_FILE).exists() => {
+                Some(read_file(DEFAULT_CONFIG_FILE)?)
+            }
+            Err(_) => None,
+        };
+
+        AppConfig::load_from(file, |key| env::var(key).ok())
+    }
+
+    /// Builds the configuration from an optional TOML document and an environment `lookup`.
+    pub fn load_from(
+        file: Option<String>,
+        lookup: impl Fn(&str) -> Option<String>,
+    ) -> Result<AppConfig, ConfigError> {
+        let environment = match lookup("APP_ENV") {
+            Some(environment) => environment
+                .parse::<Environment>()
+                .map_err(|err| ConfigError(vec![format!("APP_ENV is invalid: {}", err)]))?,
+            None => Environment::default(),
+        };
+
+        let mut config = match file {
+            Some(file) => from_toml(&file, environment)?,
+            None => AppConfig::default(),
+        };
+        config.environment = environment;
+
+        let mut errors: Vec<String> = Vec::new();
+        config.apply_env(&lookup, &mut errors);
+        config.validate(&mut errors);
+
+        if errors.is_empty() {
+            Ok(config)
+        } else {
+            Err(ConfigError(errors))
+        }
+    }
+
+    fn apply_env(&mut self, lookup: &impl Fn(&str) -> Option<String>, errors: &mut Vec<String>) {
+        override_with(lookup, "APP_HOST", &mut self.server.host, errors);
+        override_with(lookup, "APP_PORT", &mut self.server.port, errors);
+        override_with(
+            lookup,
+            "APP_BODY_LIMIT",
+            &mut self.server.body_limit,
+            errors,
+        );
+        override_with(
+            lookup,
+            "APP_REQUEST_TIMEOUT",
+            &mut self.server.request_timeout,
+            errors,
+        );
+        if let Some(origins) = lookup("APP_CORS_ORIGINS") {
+            self.server.cors_origins = origins
+                .split(',')
+                .map(|origin| origin.trim().to_string())
+                .filter(|origin| !origin.is_empty())
+                .collect();
+        }
+
+        override_with(lookup, "DATABASE_URL", &mut self.database.url, errors);
+        override_with(
+            lookup,
+            "DATABASE_POOL_SIZE",
+            &mut self.database.pool_size,
+            errors,
+        );
+        override_option(
+            lookup,
+            "DATABASE_MIN_IDLE",
+            &mut self.database.min_idle,
+            errors,
+        );
+        override_with(
+            lookup,
+            "DATABASE_CONNECTION_TIMEOUT",
+            &mut self.database.connection_timeout,
+            errors,
+        );
+        override_with(
+            lookup,
+            "DATABASE_MAX_LIFETIME",
+            &mut self.database.max_lifetime,
+            errors,
+        );
+        override_with(
+            lookup,
+            "DATABASE_IDLE_TIMEOUT",
+            &mut self.database.idle_timeout,
+            errors,
+        );
+        override_with(
+            lookup,
+            "DATABASE_TEST_ON_CHECKOUT",
+            &mut self.database.test_on_checkout,
+            errors,
+        );
+        override_with(
+            lookup,
+            "DATABASE_MIGRATIONS",
+            &mut self.database.migrations,
+            errors,
+        );
+
+        override_with(lookup, "RUST_LOG", &mut self.log.level, errors);
+        override_with(lookup, "APP_LOG_FORMAT", &mut self.log.format, errors);
+
+        override_with(lookup, "TRACING_ENABLED", &mut self.tracing.enabled, errors);
+        override_with(
+            lookup,
+            "OTEL_EXPORTER_OTLP_ENDPOINT",
+            &mut self.tracing.otlp_endpoint,
+            errors,
+        );
+        override_with(
+            lookup,
+            "OTEL_SERVICE_NAME",
+            &mut self.tracing.service_name,
+            errors,
+        );
+        override_with(
+            lookup,
+            "TRACING_SAMPLE_RATIO",
+            &mut self.tracing.sample_ratio,
+            errors,
+        );
+
+        override_with(
+            lookup,
+            "JWT_ALGORITHM",
+            &mut self.auth.jwt_algorithm,
+            errors,
+        );
+        override_option(lookup, "JWT_SECRET", &mut self.auth.jwt_secret, errors);
+        override_option(
+            lookup,
+            "JWT_PUBLIC_KEY_FILE",
+            &mut self.auth.jwt_public_key_file,
+            errors,
+        );
+        override_option(lookup, "JWT_ISSUER", &mut self.auth.jwt_issuer, errors);
+        override_option(lookup, "JWT_AUDIENCE", &mut self.auth.jwt_audience, errors);
+    }
+
+    fn validate(&self, errors: &mut Vec<String>) {
+        if self.server.port == 0 {
+            errors.push(String::from("server.port (APP_PORT) must not be 0"));
+        }
+        if self.server.body_limit == 0 {
+            errors.push(String::from(
+                "server.body_limit (APP_BODY_LIMIT) must be at least 1 byte",
+            ));
+        }
+        if self.server.request_timeout == 0 {
+            errors.push(String::from(
+                "server.request_timeout (APP_REQUEST_TIMEOUT) must be at least 1 second",
+            ));
+        }
+        for origin in &self.server.cors_origins {
+            if origin != "*" && HeaderValue::from_str(origin).is_err() {
+                errors.push(format!(
+                    "server.cors_origins (APP_CORS_ORIGINS) has an invalid origin '{}'",
+                    origin
+                ));
+            }
+        }
+
+        if self.database.url.is_empty() {
+            errors.push(String::from("database.url (DATABASE_URL) must be provided"));
+        }
+        if self.database.pool_size == 0 {
+            errors.push(String::from(
+                "database.pool_size (DATABASE_POOL_SIZE) must be at least 1",
+            ));
+        }
+        if let Some(min_idle) = self.database.min_idle {
+            if min_idle > self.database.pool_size {
+                errors.push(format!(
+                    "database.min_idle (DATABASE_MIN_IDLE) must not exceed the pool size of {}",
+                    self.database.pool_size
+                ));
+            }
+        }
+        if self.database.connection_timeout == 0 {
+            errors.push(String::from(
+                "database.connection_timeout (DATABASE_CONNECTION_TIMEOUT) must be at least 1 second",
+            ));
+        }
+
+        if !(0.0..=1.0).contains(&self.tracing.sample_ratio) {
+            errors.push(String::from(
+                "tracing.sample_ratio (TRACING_SAMPLE_RATIO) must be between 0 and 1",
+            ));
+        }
+        if self.tracing.enabled && self.tracing.otlp_endpoint.is_empty() {
+            errors.push(String::from(
+                "tracing.otlp_endpoint (OTEL_EXPORTER_OTLP_ENDPOINT) must be provided when tracing is enabled",
+            ));
+        }
+
+        match self.auth.jwt_algorithm.as_str() {
+            "HS256" if self.auth.jwt_secret.is_none() => errors.push(String::from(
+                "auth.jwt_secret (JWT_SECRET) must be provided for HS256",
+            )),
+            "RS256" if self.auth.jwt_public_key_file.is_none() => errors.push(String::from(
+                "auth.jwt_public_key_file (JWT_PUBLIC_KEY_FILE) must be provided for RS256",
+            )),
+            "HS256" | "RS256" => {}
+            other => errors.push(format!(
+                "auth.jwt_algorithm (JWT_ALGORITHM) must be HS256 or RS256, got '{}'",
+                other
+            )),
+        }
+    }
+}
+
+fn read_file(file: &str) -> Result<String, ConfigError> {
+    fs::read_to_string(file)
+        .map_err(|err| ConfigError(vec![format!("unable to read '{}': {}", file, err)]))
+}
+
+fn from_toml(file: &str, environment: Environment) -> Result<AppConfig, ConfigError> {
+    let mut table: Table = file
+        .parse()
+        .map_err(|err| ConfigError(vec![format!("invalid TOML: {}", err)]))?;
+
+    if let Some(Value::Table(mut profiles)) = table.remove("profile") {
+        if let Some(Value::Table(profile)) = profiles.remove(environment.name()) {
+            merge(&mut table, profile);
+        }
+    }
+
+    table
+        .try_into()
+        .map_err(|err| ConfigError(vec![format!("invalid TOML: {}", err)]))
+}
+
+/// Deep merges `overlay` into `base`, values of `overlay` winning.
+fn merge(base: &mut Table, overlay: Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base)), Value::Table(overlay)) => merge(base, overlay),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn override_with<T>(
+    lookup: &impl Fn(&str) -> Option<String>,
+    key: &str,
+    target: &mut T,
+    errors: &mut Vec<String>,
+) where
+    T: FromStr,
+    T::Err: Display,
+{
+    if let Some(value) = lookup(key) {
+        match value.parse::<T>() {
+            Ok(value) => *target = value,
+            Err(err) => errors.push(format!("{} '{}' is invalid: {}", key, value, err)),
+        }
+    }
+}
+
+fn override_option<T>(
+    lookup: &impl Fn(&str) -> Option<String>,
+    key: &str,
+    target: &mut Option<T>,
+    errors: &mut Vec<String>,
+) where
+    T: FromStr,
+    T::Err: Display,
+{
+    if let Some(value) = lookup(key) {
+        match value.parse::<T>() {
+            Ok(value) => *target = Some(value),
+            Err(err) => errors.push(format!("{} '{}' is invalid: {}", key, value, err)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn lookup(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        move |key| vars.get(key).cloned()
+    }
+
+    const REQUIRED: [(&str, &str); 2] = [
+        ("DATABASE_URL", "postgres://localhost/core"),
+        ("JWT_SECRET", "secret"),
+    ];
+
+    #[test]
+    fn no_timeout_on_env() {
+        // Given 'there is no timeout in environment variable'
+        let lookup = lookup(&REQUIRED);
+
+        // When 'the application loads the configuration'
+        let config = AppConfig::load_from(None, lookup).unwrap();
+
+        // Then 'the timeout should be the default'
+        assert_eq!(config.database.connection_timeout, 30);
+    }
+
+    #[test]
+    fn invalid_timeout_on_env() {
+        // Given 'there is a invalid timeout in environment variable'
+        let lookup = lookup(&[
+            REQUIRED[0],
+            REQUIRED[1],
+            ("DATABASE_CONNECTION_TIMEOUT", "F"),
+        ]);
+
+        // When 'the application loads the configuration'
+        let config = AppConfig::load_from(None, lookup);
+
+        // Then 'the configuration should be rejected'
+        assert_eq!(
+            config,
+            Err(ConfigError(vec![String::from(
+                "DATABASE_CONNECTION_TIMEOUT 'F' is invalid: invalid digit found in string"
+            )]))
+        );
+    }
+
+    #[test]
+    fn valid_timeout_on_env() {
+        // Given 'there is a valid timeout in environment variable'
+        let lookup = lookup(&[
+            REQUIRED[0],
+            REQUIRED[1],
+            ("DATABASE_CONNECTION_TIMEOUT", "10"),
+        ]);
+
+        // When 'the application loads the configuration'
+        let config = AppConfig::load_from(None, lookup).unwrap();
+
+        // Then 'the timeout should be the value from the environment variable'
+        assert_eq!(config.database.connection_timeout, 10);
+    }
+
+    #[test]
+    fn no_database_url_on_env() {
+        // Given 'there is not a database url in environment variable'
+        let lookup = lookup(&[REQUIRED[1]]);
+
+        // When 'the application loads the configuration'
+        let config = AppConfig::load_from(None, lookup);
+
+        // Then 'the result should be an Error'
+        assert_eq!(
+            config,
+            Err(ConfigError(vec![String::from(
+                "database.url (DATABASE_URL) must be provided"
+            )]))
+        );
+    }
+
+    #[test]
+    fn profile_overrides_file_and_env_overrides_profile() {
+        // Given 'a file with a production profile'
+        let file = r#"
+            [server]
+            port = 8080
+            cors_origins = ["https://showroom.example"]
+
+            [log]
+            format = "text"
+
+            [profile.production.server]
+            port = 80
+
+            [profile.production.log]
+            format = "json"
+        "#;
+        let lookup = lookup(&[
+            REQUIRED[0],
+            REQUIRED[1],
+            ("APP_ENV", "production"),
+            ("APP_HOST", "127.0.0.1"),
+        ]);
+
+        // When 'the application loads the configuration'
+        let config = AppConfig::load_from(Some(file.to_string()), lookup).unwrap();
+
+        // Then 'the profile should override the file and the environment the profile'
+        assert_eq!(config.environment, Environment::Production);
+        assert_eq!(config.server.host, "127.0.0.1");
+        assert_eq!(config.server.port, 80);
+        assert_eq!(config.server.cors_origins, vec!["https://showroom.example"]);
+        assert_eq!(config.log.format, LogFormat::Json);
+    }
+
+    #[test]
+    fn every_error_is_reported() {
+        // Given 'several invalid settings'
+        let lookup = lookup(&[
+            ("APP_PORT", "0"),
+            ("DATABASE_POOL_SIZE", "2"),
+            ("DATABASE_MIN_IDLE", "5"),
+            ("JWT_ALGORITHM", "none"),
+        ]);
+
+        // When 'the application loads the configuration'
+        let config = AppConfig::load_from(None, lookup);
+
+        // Then 'every problem should be reported'
+        assert_eq!(config.unwrap_err().0.len(), 4);
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        // Given 'a file with a typo'
+        let file = "[server]\nprot = 8080\n";
+
+        // When 'the application loads the configuration'
+        let config = AppConfig::load_from(Some(file.to_string()), lookup(&REQUIRED));
+
+        // Then 'the typo should be reported'
+        assert!(config.is_err());
+    }
+}
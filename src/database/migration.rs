@@ -0,0 +1,180 @@
+use diesel::migration::MigrationSource;
+use diesel::pg::Pg;
+use diesel::PgConnection;
+use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
+use log::{error, info, warn};
+use std::collections::HashSet;
+use std::error::Error;
+
+use crate::config::config::MigrationMode;
+
+/// The `migrations/` directory, compiled into the binary.
+pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
+
+type MigrationResult<T> = Result<T, Box<dyn Error + Send + Sync>>;
+
+/// Versions of the embedded migrations not yet applied to the database.
+pub fn pending_migrations(connection: &mut PgConnection) -> MigrationResult<Vec<String>> {
+    let pending = connection.pending_migrations(MIGRATIONS)?;
+    Ok(pending
+        .iter()
+        .map(|migration| migration.name().to_string())
+        .collect())
+}
+
+/// Versions applied to the database that this binary does not know about.
+pub fn unknown_migrations(connection: &mut PgConnection) -> MigrationResult<Vec<String>> {
+    let applied: Vec<String> = connection
+        .applied_migrations()?
+        .iter()
+        .map(|version| version.to_string())
+        .collect();
+    let embedded: Vec<String> = MigrationSource::<Pg>::migrations(&MIGRATIONS)?
+        .iter()
+        .map(|migration| migration.name().version().to_string())
+        .collect();
+
+    Ok(versions_ahead(&applied, &embedded))
+}
+
+/// Brings the schema up to date according to `mode`, refusing a database ahead of the binary.
+pub fn prepare_database(connection: &mut PgConnection, mode: MigrationMode) -> MigrationResult<()> {
+    let unknown = unknown_migrations(connection)?;
+    if !unknown.is_empty() {
+        error!(
+            "Database has migrations this binary does not know about: {}",
+            unknown.join(", ")
+        );
+        return Err(format!(
+            "Database is ahead of the application, unknown migrations: {}",
+            unknown.join(", ")
+        )
+        .into());
+    }
+
+    let pending = pending_migrations(connection)?;
+    if pending.is_empty() {
+        info!("Database schema is up to date");
+        return Ok(());
+    }
+
+    match mode {
+        MigrationMode::Off => warn!(
+            "{} pending migration(s) left unapplied: {}",
+            pending.len(),
+            pending.join(", ")
+        ),
+        MigrationMode::DryRun => {
+            for migration in &pending {
+                info!("Pending migration (dry run): {}", migration);
+            }
+        }
+        MigrationMode::Apply => {
+            for version in connection.run_pending_migrations(MIGRATIONS)? {
+                info!("Applied migration: {}", version);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Every embedded migration name, paired with whether it has been applied.
+pub fn migration_status(connection: &mut PgConnection) -> MigrationResult<Vec<(String, bool)>> {
+    let applied: HashSet<String> = connection
+        .applied_migrations()?
+        .iter()
+        .map(|version| version.to_string())
+        .collect();
+    let mut status: Vec<(String, bool)> = MigrationSource::<Pg>::migrations(&MIGRATIONS)?
+        .iter()
+        .map(|migration| {
+            let name = migration.name();
+            (
+                name.to_string(),
+                applied.contains(&name.version().to_string()),
+            )
+        })
+        .collect();
+    status.sort();
+    Ok(status)
+}
+
+/// Reverts the last `steps` applied migrations, returning their versions.
+pub fn revert_migrations(
+    connection: &mut PgConnection,
+    steps: u32,
+) -> MigrationResult<Vec<String>> {
+    let mut reverted: Vec<String> = Vec::new();
+    for _ in 0..steps {
+        let version = connection.revert_last_migration(MIGRATIONS)?;
+        info!("Reverted migration: {}", version);
+        reverted.push(version.to_string());
+    }
+    Ok(reverted)
+}
+
+fn versions_ahead(applied: &[String], embedded: &[String]) -> Vec<String> {
+    let embedded: HashSet<&String> = embedded.iter().collect();
+    let mut ahead: Vec<String> = applied
+        .iter()
+        .filter(|version| !embedded.contains(version))
+        .cloned()
+        .collect();
+    ahead.sort();
+    ahead
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn versions(versions: &[&str]) -> Vec<String> {
+        versions.iter().map(|version| version.to_string()).collect()
+    }
+
+    #[test]
+    fn database_behind_binary_is_not_ahead() {
+        // Given 'a database missing the latest embedded migration'
+        let applied = versions(&["20241120000232"]);
+        let embedded = versions(&["20241120000232", "20261018140000"]);
+
+        // When 'the versions are compared'
+        let ahead = versions_ahead(&applied, &embedded);
+
+        // Then 'no version should be unknown'
+        assert!(ahead.is_empty());
+    }
+
+    #[test]
+    fn database_ahead_of_binary_lists_unknown_versions() {
+        // Given 'a database migrated by a newer binary'
+        let applied = versions(&["20241120000232", "20991231000000"]);
+        let embedded = versions(&["20241120000232"]);
+
+        // When 'the versions are compared'
+        let ahead = versions_ahead(&applied, &embedded);
+
+        // Then 'the newer version should be reported'
+        assert_eq!(ahead, versions(&["20991231000000"]));
+    }
+
+    #[test]
+    fn embedded_migrations_have_unique_versions() {
+        // Given 'the migrations compiled into the binary'
+        let embedded: Vec<String> = MigrationSource::<Pg>::migrations(&MIGRATIONS)
+            .unwrap()
+            .iter()
+            .map(|migration| migration.name().version().to_string())
+            .collect();
+
+        // When 'they are sorted and deduplicated'
+        let mut sorted = embedded.clone();
+        sorted.sort();
+        sorted.dedup();
+
+        // Then 'every migration should have its own version'
+        assert_eq!(sorted.len(), embedded.len());
+        assert_eq!(sorted.first().map(String::as_str), Some("00000000000000"));
+    }
+}
@@ -1 +1,3 @@
-pub mod database;
\ No newline at end of file
+#[allow(clippy::module_inception)]
+pub mod database;
+pub mod migration;
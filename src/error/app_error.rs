@@ -0,0 +1,264 @@
+use std::fmt::{Display, Formatter};
+use std::num::ParseIntError;
+use std::str::ParseBoolError;
+
+use axum::http::header::WWW_AUTHENTICATE;
+use axum::http::{HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use diesel::r2d2::PoolError;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+
+use crate::error::problem::{FieldError, Problem};
+
+/// Errors surfaced by the services, each one mapped to the HTTP status the client gets.
+///
+/// Messages are meant for clients; database details are logged and never sent back.
+#[derive(Debug, PartialEq)]
+pub enum AppError {
+    Unauthorized(String),
+    Forbidden(String),
+    NotFound(String),
+    Validation(Vec<FieldError>),
+    Conflict(String),
+    Unavailable(String),
+    Internal(String),
+}
+
+impl AppError {
+    pub fn validation(field: impl Into<String>, message: impl Into<String>) -> AppError {
+        AppError::Validation(vec![FieldError::new(field, message)])
+    }
+
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            AppError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            AppError::Forbidden(_) => StatusCode::FORBIDDEN,
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            AppError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    pub fn problem_type(&self) -> &'static str {
+        match self {
+            AppError::Unauthorized(_) => "/problems/unauthorized",
+            AppError::Forbidden(_) => "/problems/forbidden",
+            AppError::NotFound(_) => "/problems/not-found",
+            AppError::Validation(_) => "/problems/validation-error",
+            AppError::Conflict(_) => "/problems/conflict",
+            AppError::Unavailable(_) => "/problems/service-unavailable",
+            AppError::Internal(_) => "/problems/internal-error",
+        }
+    }
+}
+
+impl Display for AppError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AppError::Unauthorized(message)
+            | AppError::Forbidden(message)
+            | AppError::NotFound(message)
+            | AppError::Conflict(message)
+            | AppError::Unavailable(message)
+            | AppError::Internal(message) => write!(f, "{}", message),
+            AppError::Validation(errors) => write!(
+                f,
+                "Invalid request: {}",
+                errors
+                    .iter()
+                    .map(|err| format!("{} {}", err.field, err.message))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl From<AppError> for Problem {
+    fn from(err: AppError) -> Self {
+        let mut problem = Problem::new(err.problem_type(), err.status_code(), None);
+        match err {
+            AppError::Validation(errors) => {
+                problem.detail = Some(String::from("One or more fields are invalid"));
+                problem.errors = errors;
+            }
+            other => problem.detail = Some(other.to_string()),
+        }
+        problem
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let unauthorized = matches!(self, AppError::Unauthorized(_));
+        let mut response = Problem::from(self).into_response();
+        if unauthorized {
+            response
+                .headers_mut()
+                .insert(WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+        }
+        response
+    }
+}
+
+impl From<DieselError> for AppError {
+    fn from(err: DieselError) -> Self {
+        match err {
+            DieselError::NotFound => AppError::NotFound(String::from("Resource not found")),
+            DieselError::DatabaseError(kind, _) => match kind {
+                DatabaseErrorKind::UniqueViolation => {
+                    AppError::Conflict(String::from("Resource already exists"))
+                }
+                DatabaseErrorKind::ForeignKeyViolation => AppError::Conflict(String::from(
+                    "Resource references, or is referenced by, another resource",
+                )),
+                DatabaseErrorKind::NotNullViolation | DatabaseErrorKind::CheckViolation => {
+                    AppError::validation("body", "has missing or invalid fields")
+                }
+                DatabaseErrorKind::ClosedConnection => {
+                    AppError::Unavailable(String::from("Database is unavailable"))
+                }
+                _ => AppError::Internal(String::from("Internal server error")),
+            },
+            _ => AppError::Internal(String::from("Internal server error")),
+        }
+    }
+}
+
+impl From<PoolError> for AppError {
+    fn from(_: PoolError) -> Self {
+        AppError::Unavailable(String::from("Database is unavailable"))
+    }
+}
+
+impl From<ParseIntError> for AppError {
+    fn from(err: ParseIntError) -> Self {
+        AppError::validation("filter_term", format!("must be a number: {}", err))
+    }
+}
+
+impl From<ParseBoolError> for AppError {
+    fn from(_: ParseBoolError) -> Self {
+        AppError::validation("filter_term", "must be 'true' or 'false'")
+    }
+}
+
+impl From<uuid::Error> for AppError {
+    fn from(err: uuid::Error) -> Self {
+        AppError::validation("filter_term", format!("must be a UUID: {}", err))
+    }
+}
+
+impl From<chrono::ParseError> for AppError {
+    fn from(err: chrono::ParseError) -> Self {
+        AppError::validation(
+            "filter_term",
+            format!("must be an RFC 3339 timestamp: {}", err),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use diesel::result::DatabaseErrorInformation;
+
+    struct Info;
+
+    impl DatabaseErrorInformation for Info {
+        fn message(&self) -> &str {
+            "duplicate key value violates unique constraint \"brands_pkey\""
+        }
+        fn details(&self) -> Option<&str> {
+            None
+        }
+        fn hint(&self) -> Option<&str> {
+            None
+        }
+        fn table_name(&self) -> Option<&str> {
+            None
+        }
+        fn column_name(&self) -> Option<&str> {
+            None
+        }
+        fn constraint_name(&self) -> Option<&str> {
+            None
+        }
+        fn statement_position(&self) -> Option<i32> {
+            None
+        }
+    }
+
+    #[test]
+    fn diesel_not_found_is_not_found() {
+        // Given 'diesel could not find a row'
+        let err = DieselError::NotFound;
+
+        // When 'the error is converted'
+        let app_error = AppError::from(err);
+
+        // Then 'the status should be 404'
+        assert_eq!(app_error.status_code(), StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn unique_violation_is_conflict_without_database_details() {
+        // Given 'a unique constraint violation'
+        let err = DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, Box::new(Info));
+
+        // When 'the error is converted'
+        let app_error = AppError::from(err);
+
+        // Then 'the status should be 409 and the constraint should not leak'
+        assert_eq!(app_error.status_code(), StatusCode::CONFLICT);
+        assert!(!app_error.to_string().contains("brands_pkey"));
+    }
+
+    #[test]
+    fn invalid_filter_term_is_validation() {
+        // Given 'a filter term that is not a number'
+        let err = "F".parse::<i32>().unwrap_err();
+
+        // When 'the error is converted'
+        let app_error = AppError::from(err);
+
+        // Then 'the status should be 422'
+        assert_eq!(app_error.status_code(), StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[test]
+    fn unknown_database_error_is_internal() {
+        // Given 'an unexpected database error'
+        let err = DieselError::DatabaseError(DatabaseErrorKind::Unknown, Box::new(Info));
+
+        // When 'the error is converted'
+        let app_error = AppError::from(err);
+
+        // Then 'the status should be 500 with a generic message'
+        assert_eq!(app_error.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(app_error.to_string(), "Internal server error");
+    }
+
+    #[test]
+    fn validation_error_is_problem_with_field_errors() {
+        // Given 'a validation error on two fields'
+        let app_error = AppError::Validation(vec![
+            FieldError::new("price", "must not be negative"),
+            FieldError::new("year", "must be at least 1900"),
+        ]);
+
+        // When 'the error is converted to a problem'
+        let problem = Problem::from(app_error);
+
+        // Then 'the problem should list both fields'
+        assert_eq!(problem.status, 422);
+        assert_eq!(problem.problem_type, "/problems/validation-error");
+        assert_eq!(problem.title, "Unprocessable Entity");
+        assert_eq!(problem.errors.len(), 2);
+        assert_eq!(problem.errors[0].field, "price");
+    }
+}
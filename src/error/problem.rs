@@ -0,0 +1,180 @@
+use axum::body::to_bytes;
+use axum::extract::Request;
+use axum::http::header::{CONTENT_LENGTH, CONTENT_TYPE};
+use axum::http::{HeaderValue, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::request_id::RequestId;
+
+pub const PROBLEM_CONTENT_TYPE: &str = "application/problem+json";
+
+/// Largest plain-text error body turned into a problem `detail` (axum rejections are short).
+const MAX_DETAIL_BYTES: usize = 16 * 1024;
+
+/// A RFC 7807 problem details document.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Problem {
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    pub title: String,
+    pub status: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    /// The `X-Request-Id` of the failed request, to find its log records.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub request_id: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub errors: Vec<FieldError>,
+}
+
+/// A single violated field, `field` being its JSON path, e.g. `address.zip_code` or `[2].price`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> FieldError {
+        FieldError {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl Problem {
+    pub fn new(problem_type: &str, status: StatusCode, detail: Option<String>) -> Problem {
+        Problem {
+            problem_type: problem_type.to_string(),
+            title: status
+                .canonical_reason()
+                .unwrap_or("Unknown Error")
+                .to_string(),
+            status: status.as_u16(),
+            detail,
+            instance: None,
+            request_id: None,
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn status_code(&self) -> StatusCode {
+        StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+}
+
+impl IntoResponse for Problem {
+    fn into_response(self) -> Response {
+        let status = self.status_code();
+        let body = serde_json::to_vec(&self).unwrap_or_default();
+
+        let mut response = (status, body).into_response();
+        response
+            .headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static(PROBLEM_CONTENT_TYPE));
+        response.extensions_mut().insert(self);
+        response
+    }
+}
+
+/// Middleware rendering every error response as `application/problem+json`.
+///
+/// Problems raised by the handlers get the request path as `instance` and the request id, any
+/// other error (extractor rejections, unknown routes, wrong methods) is wrapped into a problem.
+pub async fn problem_details(request: Request, next: Next) -> Response {
+    let instance = request.uri().path().to_string();
+    let request_id = request
+        .extensions()
+        .get::<RequestId>()
+        .map(|id| id.0.clone());
+    let response = next.run(request).await;
+
+    let status = response.status();
+    if !status.is_client_error() && !status.is_server_error() {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let mut problem = match parts.extensions.remove::<Problem>() {
+        Some(problem) => problem,
+        None => {
+            let detail = match to_bytes(body, MAX_DETAIL_BYTES).await {
+                Ok(bytes) => String::from_utf8_lossy(&bytes).trim().to_string(),
+                Err(err) => {
+                    warn!("Unable to read error response body, error: {}", err);
+                    String::new()
+                }
+            };
+            Problem::new(
+                "about:blank",
+                status,
+                Some(detail).filter(|detail| !detail.is_empty()),
+            )
+        }
+    };
+    problem.instance = Some(instance);
+    problem.request_id = request_id;
+
+    // Appended, multi-valued headers such as `Vary` or `Set-Cookie` keep every value.
+    let mut response = problem.into_response();
+    for (name, value) in parts.headers.iter() {
+        if name != CONTENT_TYPE && name != CONTENT_LENGTH {
+            response.headers_mut().append(name.clone(), value.clone());
+        }
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::header::{SET_COOKIE, VARY};
+    use axum::middleware;
+    use axum::routing::get;
+    use axum::Router;
+    use tower::Service;
+
+    #[tokio::test]
+    async fn multi_valued_headers_are_kept() {
+        // Given 'an error response setting two cookies and two Vary headers'
+        let mut app = Router::new()
+            .route(
+                "/",
+                get(|| async {
+                    Response::builder()
+                        .status(StatusCode::NOT_FOUND)
+                        .header(SET_COOKIE, "a=1")
+                        .header(SET_COOKIE, "b=2")
+                        .header(VARY, "accept")
+                        .header(VARY, "origin")
+                        .body(Body::empty())
+                        .unwrap()
+                }),
+            )
+            .layer(middleware::from_fn(problem_details));
+
+        // When 'the response is turned into a problem'
+        let request = Request::builder().uri("/").body(Body::empty()).unwrap();
+        let response = app.call(request).await.unwrap();
+
+        // Then 'every value should be kept'
+        let values = |name| {
+            response
+                .headers()
+                .get_all(name)
+                .iter()
+                .map(|value| value.to_str().unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(values(SET_COOKIE), vec!["a=1", "b=2"]);
+        assert_eq!(values(VARY), vec!["accept", "origin"]);
+        assert_eq!(response.headers()[CONTENT_TYPE], PROBLEM_CONTENT_TYPE);
+    }
+}
@@ -1,2 +1,12 @@
+pub mod auth;
+pub mod cli;
+pub mod config;
+pub mod database;
+pub mod error;
+pub mod metrics;
 pub mod models;
+pub mod resource;
 pub mod schema;
+pub mod service;
+pub mod telemetry;
+pub mod utils;
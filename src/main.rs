@@ -1,53 +1,153 @@
-use crate::database::database::get_connection_pool;
-use axum::extract::Request;
-use axum::http::Method;
+use axum::extract::{DefaultBodyLimit, Request};
+use axum::http::header::{AUTHORIZATION, CONTENT_TYPE};
+use axum::http::{HeaderName, HeaderValue, Method};
+use axum::middleware;
 use axum::Router;
 use axum::ServiceExt;
+use clap::Parser;
 use dotenvy::dotenv;
 use log::{error, info};
+use showroom_api::auth::api_key::authenticate_api_key;
+use showroom_api::auth::jwt::{authenticate, JwtVerifier};
+use showroom_api::cli::args::{Cli, Command};
+use showroom_api::cli::commands;
+use showroom_api::config::config::{AppConfig, ServerConfig};
+use showroom_api::database::database::{checkout, get_lazy_connection_pool};
+use showroom_api::database::migration::prepare_database;
+use showroom_api::error::problem::problem_details;
+use showroom_api::metrics::http::track_requests;
+use showroom_api::telemetry::http::trace_requests;
+use showroom_api::telemetry::logging::init_logger;
+use showroom_api::utils::request_id::{request_id, REQUEST_ID_HEADER};
+use std::process;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::signal;
 use tower::Layer;
 use tower_http::cors::Any;
-use tower_http::cors::CorsLayer;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+use tower_http::timeout::TimeoutLayer;
 
 use tower_http::normalize_path::NormalizePathLayer;
 
-use crate::resource::brand_controller;
-use crate::resource::post_controller;
-
-mod database;
-mod models;
-mod resource;
-mod schema;
-mod service;
-mod utils;
+use showroom_api::resource::api_key_controller;
+use showroom_api::resource::brand_controller;
+use showroom_api::resource::car_controller;
+use showroom_api::resource::database_controller;
+use showroom_api::resource::health_controller;
+use showroom_api::resource::listing_controller;
+use showroom_api::resource::metrics_controller;
+use showroom_api::resource::model_controller;
+use showroom_api::resource::post_controller;
+use showroom_api::resource::post_migration_controller;
+use showroom_api::resource::seller_controller;
+use showroom_api::resource::version_controller;
 
 #[tokio::main]
 async fn main() {
     dotenv().ok();
-    env_logger::init();
+    let cli = Cli::parse();
+    let config = AppConfig::load().unwrap_or_else(|err| panic!("{}", err));
+    let tracer_provider = init_logger(&config.log, &config.tracing)
+        .unwrap_or_else(|err| panic!("Unable to initialize logging, error: {}", err));
+    info!("Loaded '{}' configuration", config.environment.name());
+
+    let result = match cli.command.unwrap_or(Command::Serve) {
+        Command::Serve => {
+            serve(config).await;
+            Ok(())
+        }
+        Command::Migrate(command) => commands::migrate(&config, command),
+        Command::Seed { file } => commands::seed(&config, &file, &cli.operator),
+        Command::Export(command) => commands::export(&config, command),
+        Command::CreateApiKey(args) => commands::create_api_key(&config, args, &cli.operator),
+        Command::MigratePosts(args) => commands::migrate_posts(&config, args, &cli.operator),
+    };
+
+    if let Err(err) = &result {
+        error!("Command failed, error: {}", err);
+    }
+
+    if let Some(provider) = tracer_provider {
+        info!("Flushing spans...");
+        if let Err(err) = provider.shutdown() {
+            error!("Unable to flush spans, error: {}", err);
+        }
+    }
+
+    if result.is_err() {
+        process::exit(1);
+    }
+}
+
+async fn serve(config: AppConfig) {
+    info!("Creating database connection pool...");
+    let pool = Arc::new(get_lazy_connection_pool(&config.database));
+    info!("Database connection pool created.");
 
-    info!("Establishing database connection pool...");
-    let pool = Arc::new(
-        get_connection_pool().unwrap_or_else(|_| panic!("Unable to establish database connection")),
+    // An unreachable database is reported by /health/ready, a database the binary can't work
+    // with is not worth serving.
+    info!("Checking database migrations...");
+    match checkout(&pool, "migration") {
+        Ok(mut connection) => {
+            prepare_database(&mut connection, config.database.migrations)
+                .unwrap_or_else(|err| panic!("Unable to prepare database, error: {}", err));
+            info!("Database migrations checked.");
+        }
+        Err(err) => error!(
+            "Unable to check database migrations, serving without a database, error: {}",
+            err
+        ),
+    }
+
+    let verifier = Arc::new(
+        JwtVerifier::from_config(&config.auth)
+            .unwrap_or_else(|err| panic!("Unable to configure authentication, error: {}", err)),
     );
-    info!("Database connection pool established.");
+
+    tokio::spawn(metrics_controller::refresh_published_posts(pool.clone()));
 
     info!("Establishing server configurations");
-    let cors = CorsLayer::new()
-        .allow_methods([Method::GET, Method::POST, Method::PATCH])
-        .allow_origin(Any);
+    let api = Router::new()
+        .merge(post_controller::router(pool.clone()))
+        .merge(brand_controller::router(pool.clone()))
+        .merge(model_controller::router(pool.clone()))
+        .merge(version_controller::router(pool.clone()))
+        .merge(car_controller::router(pool.clone()))
+        .merge(seller_controller::router(pool.clone()))
+        .merge(listing_controller::router(pool.clone()))
+        .merge(post_migration_controller::router(pool.clone()))
+        .merge(api_key_controller::router(pool.clone()))
+        .merge(database_controller::router(pool.clone()))
+        .layer(middleware::from_fn_with_state(
+            pool.clone(),
+            authenticate_api_key,
+        ))
+        .layer(middleware::from_fn_with_state(verifier, authenticate))
+        .layer(DefaultBodyLimit::max(config.server.body_limit))
+        .layer(TimeoutLayer::new(Duration::from_secs(
+            config.server.request_timeout,
+        )))
+        .layer(middleware::from_fn(problem_details))
+        .layer(middleware::from_fn(track_requests))
+        .layer(middleware::from_fn(trace_requests));
+    // Probes and metrics answer with their own body, unauthenticated, everything else goes to the API
     let app = NormalizePathLayer::trim_trailing_slash().layer(
-        Router::new()
-            .merge(post_controller::router(pool.clone()))
-            .merge(brand_controller::router(pool.clone()))
-            .layer(cors),
+        health_controller::router(pool.clone())
+            .merge(metrics_controller::router(pool.clone()))
+            .route_layer(middleware::from_fn(track_requests))
+            .route_layer(middleware::from_fn(trace_requests))
+            .fallback_service(api)
+            .layer(cors_layer(&config.server))
+            .layer(middleware::from_fn(request_id)),
     );
-    let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await.unwrap();
+    let address = format!("{}:{}", config.server.host, config.server.port);
+    let listener = tokio::net::TcpListener::bind(&address)
+        .await
+        .unwrap_or_else(|err| panic!("Unable to bind '{}', error: {}", address, err));
     info!("Server configurations established.");
 
-    info!("Starting server...");
+    info!("Starting server on {}...", address);
     axum::serve(listener, ServiceExt::<Request>::into_make_service(app))
         .with_graceful_shutdown(shutdown_signal())
         .await
@@ -57,6 +157,30 @@ async fn main() {
     info!("Server stopped.");
 }
 
+fn cors_layer(config: &ServerConfig) -> CorsLayer {
+    let cors = CorsLayer::new()
+        .allow_methods([Method::GET, Method::POST, Method::PATCH, Method::DELETE])
+        .allow_headers([
+            AUTHORIZATION,
+            CONTENT_TYPE,
+            HeaderName::from_static("x-api-key"),
+            HeaderName::from_static(REQUEST_ID_HEADER),
+        ])
+        .expose_headers([HeaderName::from_static(REQUEST_ID_HEADER)]);
+
+    if config.cors_origins.iter().any(|origin| origin == "*") {
+        cors.allow_origin(Any)
+    } else {
+        // Origins are validated when the configuration is loaded.
+        cors.allow_origin(AllowOrigin::list(
+            config
+                .cors_origins
+                .iter()
+                .filter_map(|origin| HeaderValue::from_str(origin).ok()),
+        ))
+    }
+}
+
 async fn shutdown_signal() {
     let ctrl_c = async {
         signal::ctrl_c()
@@ -0,0 +1,3 @@
+pub mod http;
+pub mod query;
+pub mod registry;
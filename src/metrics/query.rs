@@ -0,0 +1,45 @@
+use std::time::Instant;
+
+use diesel::connection::{Instrumentation, InstrumentationEvent};
+use tracing::field::Empty;
+use tracing::{info_span, Span};
+
+use crate::metrics::registry::metrics;
+
+/// Times every query of a connection, labelled with the service that checked it out, each query
+/// getting its own span under the span that ran it.
+pub struct QueryTimer {
+    service: &'static str,
+    started: Option<(Instant, Span)>,
+}
+
+impl QueryTimer {
+    pub fn new(service: &'static str) -> QueryTimer {
+        QueryTimer {
+            service,
+            started: None,
+        }
+    }
+}
+
+impl Instrumentation for QueryTimer {
+    fn on_connection_event(&mut self, event: InstrumentationEvent<'_>) {
+        match event {
+            InstrumentationEvent::StartQuery { .. } => {
+                let span = info_span!("query", service = self.service, outcome = Empty);
+                self.started = Some((Instant::now(), span));
+            }
+            InstrumentationEvent::FinishQuery { error, .. } => {
+                if let Some((started, span)) = self.started.take() {
+                    let outcome = if error.is_some() { "error" } else { "ok" };
+                    span.record("outcome", outcome);
+                    metrics()
+                        .query_duration
+                        .with_label_values(&[self.service, outcome])
+                        .observe(started.elapsed().as_secs_f64());
+                }
+            }
+            _ => {}
+        }
+    }
+}
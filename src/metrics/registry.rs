@@ -0,0 +1,155 @@
+use std::sync::OnceLock;
+
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry,
+    TextEncoder,
+};
+
+use crate::database::database::PoolStatus;
+
+static METRICS: OnceLock<Metrics> = OnceLock::new();
+
+/// Every metric exposed at `/metrics`.
+pub struct Metrics {
+    registry: Registry,
+    pub http_requests: IntCounterVec,
+    pub http_request_duration: HistogramVec,
+    pub pool_connections: IntGaugeVec,
+    pub pool_max_size: IntGauge,
+    pub query_duration: HistogramVec,
+    pub published_posts: IntGauge,
+}
+
+/// The process wide metrics, registered on first use.
+pub fn metrics() -> &'static Metrics {
+    METRICS.get_or_init(Metrics::new)
+}
+
+impl Metrics {
+    fn new() -> Metrics {
+        let registry = Registry::new();
+
+        let http_requests = IntCounterVec::new(
+            Opts::new("http_requests_total", "HTTP requests handled"),
+            &["method", "route", "status"],
+        )
+        .expect("valid http_requests_total metric");
+        let http_request_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "http_request_duration_seconds",
+                "Time to answer HTTP requests",
+            ),
+            &["method", "route", "status"],
+        )
+        .expect("valid http_request_duration_seconds metric");
+        let pool_connections = IntGaugeVec::new(
+            Opts::new(
+                "db_pool_connections",
+                "Database pool connections by state: idle, in_use or waiting",
+            ),
+            &["state"],
+        )
+        .expect("valid db_pool_connections metric");
+        let pool_max_size = IntGauge::new("db_pool_max_size", "Database pool maximum size")
+            .expect("valid db_pool_max_size metric");
+        let query_duration = HistogramVec::new(
+            HistogramOpts::new("db_query_duration_seconds", "Time to run database queries")
+                .buckets(vec![
+                    0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5,
+                ]),
+            &["service", "outcome"],
+        )
+        .expect("valid db_query_duration_seconds metric");
+        let published_posts =
+            IntGauge::new("showroom_published_posts", "Published posts, not deleted")
+                .expect("valid showroom_published_posts metric");
+
+        for collector in [
+            Box::new(http_requests.clone()) as Box<dyn prometheus::core::Collector>,
+            Box::new(http_request_duration.clone()),
+            Box::new(pool_connections.clone()),
+            Box::new(pool_max_size.clone()),
+            Box::new(query_duration.clone()),
+            Box::new(published_posts.clone()),
+        ] {
+            registry
+                .register(collector)
+                .expect("metric registered once");
+        }
+
+        Metrics {
+            registry,
+            http_requests,
+            http_request_duration,
+            pool_connections,
+            pool_max_size,
+            query_duration,
+            published_posts,
+        }
+    }
+
+    pub fn observe_pool(&self, status: &PoolStatus) {
+        self.pool_max_size.set(status.max_size as i64);
+        self.pool_connections
+            .with_label_values(&["idle"])
+            .set(status.idle as i64);
+        self.pool_connections
+            .with_label_values(&["in_use"])
+            .set(status.in_use as i64);
+        self.pool_connections
+            .with_label_values(&["waiting"])
+            .set(status.waiting as i64);
+    }
+
+    /// The metrics in the Prometheus text format.
+    pub fn render(&self) -> Result<String, prometheus::Error> {
+        let mut buffer: Vec<u8> = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+        Ok(String::from_utf8_lossy(&buffer).into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pool_status_is_exposed_by_state() {
+        // Given 'a pool with one connection in use'
+        let metrics = Metrics::new();
+        let status = PoolStatus {
+            max_size: 10,
+            connections: 3,
+            idle: 2,
+            in_use: 1,
+            waiting: 0,
+        };
+
+        // When 'the pool is observed and the metrics rendered'
+        metrics.observe_pool(&status);
+        let rendered = metrics.render().unwrap();
+
+        // Then 'each state should be a labelled gauge'
+        assert!(rendered.contains("db_pool_connections{state=\"idle\"} 2"));
+        assert!(rendered.contains("db_pool_connections{state=\"in_use\"} 1"));
+        assert!(rendered.contains("db_pool_max_size 10"));
+    }
+
+    #[test]
+    fn requests_are_labelled_by_route_template() {
+        // Given 'a request answered on a templated route'
+        let metrics = Metrics::new();
+
+        // When 'the request is counted'
+        metrics
+            .http_requests
+            .with_label_values(&["GET", "/v1/post/:id", "200"])
+            .inc();
+        let rendered = metrics.render().unwrap();
+
+        // Then 'the route template should be the label'
+        assert!(rendered.contains(
+            "http_requests_total{method=\"GET\",route=\"/v1/post/:id\",status=\"200\"} 1"
+        ));
+    }
+}
@@ -0,0 +1,52 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::utils::time_validation::validate_future;
+
+/// A key for machine-to-machine integrations, acting as `owner` with `scopes` as its roles.
+///
+/// Only the SHA-256 hash of the key is stored, the key itself is shown once when issued or rotated.
+#[derive(Queryable, Selectable, Insertable, Identifiable, Serialize, Debug)]
+#[diesel(table_name = crate::schema::api_keys)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ApiKey {
+    pub id: Uuid,
+    pub name: String,
+    pub owner: String,
+    pub key_prefix: String,
+    #[serde(skip_serializing)]
+    pub key_hash: String,
+    /// Elements are never NULL, the column type merely can't say so.
+    pub scopes: Vec<Option<String>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_by: String,
+    pub updated_by: Option<String>,
+    pub revoked_by: Option<String>,
+}
+
+/// An API key along with its plain text value, returned only when issued or rotated.
+#[derive(Serialize, Debug)]
+pub struct IssuedApiKey {
+    #[serde(flatten)]
+    pub api_key: ApiKey,
+    pub key: String,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct IssueApiKey {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    #[validate(length(min = 1, max = 100))]
+    pub owner: String,
+    #[validate(length(min = 1))]
+    pub scopes: Vec<String>,
+    #[validate(custom(function = "validate_future"))]
+    pub expires_at: Option<DateTime<Utc>>,
+}
@@ -2,6 +2,9 @@ use chrono::{DateTime, Utc};
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
+use validator::Validate;
+
+use crate::utils::merge_patch::required;
 
 #[derive(
     Queryable, Selectable, Serialize, Deserialize, Insertable, Identifiable, AsChangeset, Debug,
@@ -21,18 +24,28 @@ pub struct Brand {
     pub deleted_by: Option<String>,
 }
 
-#[derive(Deserialize, Insertable, Debug)]
+#[derive(Deserialize, Insertable, Validate, Debug)]
 #[diesel(table_name = crate::schema::brands)]
 pub struct CreateBrand {
+    #[validate(length(min = 1, max = 100))]
     pub name: String,
+    #[validate(url)]
     pub image_url: String,
+    #[validate(url)]
     pub thumbnail_url: String,
 }
 
-#[derive(Deserialize, AsChangeset, Debug)]
+/// A JSON Merge Patch of a brand, only the present fields are changed.
+#[derive(Deserialize, AsChangeset, Validate, Debug)]
 #[diesel(table_name = crate::schema::brands)]
 pub struct UpdateBrand {
-    pub name: String,
-    pub image_url: String,
-    pub thumbnail_url: String,
+    #[serde(default, deserialize_with = "required")]
+    #[validate(length(min = 1, max = 100))]
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "required")]
+    #[validate(url)]
+    pub image_url: Option<String>,
+    #[serde(default, deserialize_with = "required")]
+    #[validate(url)]
+    pub thumbnail_url: Option<String>,
 }
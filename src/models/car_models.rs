@@ -0,0 +1,91 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::utils::merge_patch::required;
+
+use crate::models::brand_models::Brand;
+use crate::models::model_models::Model;
+use crate::models::version_models::Version;
+
+#[derive(
+    Queryable,
+    Selectable,
+    Serialize,
+    Deserialize,
+    Insertable,
+    Identifiable,
+    Associations,
+    AsChangeset,
+    Debug,
+)]
+#[diesel(belongs_to(Version))]
+#[diesel(table_name = crate::schema::cars)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Car {
+    pub id: Uuid,
+    pub version_id: Uuid,
+    pub mileage: i32,
+    pub color: String,
+    pub armored: bool,
+    pub owner: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub created_by: String,
+    pub updated_by: Option<String>,
+    pub deleted_by: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct CarDetails {
+    #[serde(flatten)]
+    pub car: Car,
+    pub version: Version,
+    pub model: Model,
+    pub brand: Brand,
+}
+
+impl From<(Car, Version, Model, Brand)> for CarDetails {
+    fn from((car, version, model, brand): (Car, Version, Model, Brand)) -> Self {
+        CarDetails {
+            car,
+            version,
+            model,
+            brand,
+        }
+    }
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct CreateCar {
+    pub version_id: Uuid,
+    #[validate(range(min = 0))]
+    pub mileage: i32,
+    #[validate(length(min = 1, max = 100))]
+    pub color: String,
+    pub armored: bool,
+    #[validate(length(min = 1, max = 100))]
+    pub owner: String,
+}
+
+/// A JSON Merge Patch of a car, only the present fields are changed.
+#[derive(Deserialize, AsChangeset, Validate, Debug)]
+#[diesel(table_name = crate::schema::cars)]
+pub struct UpdateCar {
+    #[serde(default, deserialize_with = "required")]
+    pub version_id: Option<Uuid>,
+    #[serde(default, deserialize_with = "required")]
+    #[validate(range(min = 0))]
+    pub mileage: Option<i32>,
+    #[serde(default, deserialize_with = "required")]
+    #[validate(length(min = 1, max = 100))]
+    pub color: Option<String>,
+    #[serde(default, deserialize_with = "required")]
+    pub armored: Option<bool>,
+    #[serde(default, deserialize_with = "required")]
+    #[validate(length(min = 1, max = 100))]
+    pub owner: Option<String>,
+}
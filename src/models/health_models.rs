@@ -0,0 +1,66 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use serde::Serialize;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    Up,
+    Down,
+}
+
+/// Outcome of checking one dependency, with how long the check took.
+#[derive(Serialize, Debug)]
+pub struct ComponentHealth {
+    pub status: HealthStatus,
+    pub latency_ms: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// Overall status, down as soon as one component is down.
+#[derive(Serialize, Debug)]
+pub struct Health {
+    pub status: HealthStatus,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub components: BTreeMap<String, ComponentHealth>,
+}
+
+impl ComponentHealth {
+    pub fn up(latency: Duration, detail: Option<String>) -> ComponentHealth {
+        ComponentHealth {
+            status: HealthStatus::Up,
+            latency_ms: milliseconds(latency),
+            detail,
+        }
+    }
+
+    pub fn down(latency: Duration, detail: String) -> ComponentHealth {
+        ComponentHealth {
+            status: HealthStatus::Down,
+            latency_ms: milliseconds(latency),
+            detail: Some(detail),
+        }
+    }
+}
+
+impl Health {
+    pub fn from_components(components: BTreeMap<String, ComponentHealth>) -> Health {
+        let status = if components
+            .values()
+            .all(|component| component.status == HealthStatus::Up)
+        {
+            HealthStatus::Up
+        } else {
+            HealthStatus::Down
+        };
+
+        Health { status, components }
+    }
+}
+
+/// Milliseconds rounded to the microsecond.
+fn milliseconds(latency: Duration) -> f64 {
+    latency.as_micros() as f64 / 1000.0
+}
@@ -0,0 +1,118 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::brand_models::Brand;
+use crate::models::car_models::Car;
+use crate::models::model_models::Model;
+use crate::models::seller_models::{Seller, SellerDetails};
+use crate::models::version_models::Version;
+use crate::utils::contact_validation::validate_phone;
+
+#[derive(
+    Queryable,
+    Selectable,
+    Serialize,
+    Deserialize,
+    Insertable,
+    Identifiable,
+    Associations,
+    AsChangeset,
+    Debug,
+)]
+#[diesel(belongs_to(Car))]
+#[diesel(belongs_to(Seller))]
+#[diesel(table_name = crate::schema::listings)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Listing {
+    pub id: Uuid,
+    pub car_id: Uuid,
+    pub seller_id: Uuid,
+    pub price: i32,
+    pub exchange: bool,
+    pub phone: String,
+    pub email: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub created_by: String,
+    pub updated_by: Option<String>,
+    pub deleted_by: Option<String>,
+}
+
+/// A listing denormalized with its car, catalog entries and seller, as shown in the marketplace.
+#[derive(Serialize, Debug)]
+pub struct ListingDocument {
+    pub id: Uuid,
+    pub car_id: Uuid,
+    pub brand: String,
+    pub model: String,
+    pub version: String,
+    pub engine: String,
+    pub transmission: String,
+    pub year: i32,
+    pub body: String,
+    pub doors: i32,
+    pub mileage: i32,
+    pub color: String,
+    pub armored: bool,
+    pub price: i32,
+    pub exchange: bool,
+    pub phone: String,
+    pub email: String,
+    pub seller: SellerDetails,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl From<(Listing, Car, Version, Model, Brand, Seller)> for ListingDocument {
+    fn from(
+        (listing, car, version, model, brand, seller): (
+            Listing,
+            Car,
+            Version,
+            Model,
+            Brand,
+            Seller,
+        ),
+    ) -> Self {
+        ListingDocument {
+            id: listing.id,
+            car_id: car.id,
+            brand: brand.name,
+            model: model.name,
+            version: version.name,
+            engine: version.engine,
+            transmission: version.transmission,
+            year: version.year,
+            body: version.body,
+            doors: version.doors,
+            mileage: car.mileage,
+            color: car.color,
+            armored: car.armored,
+            price: listing.price,
+            exchange: listing.exchange,
+            phone: listing.phone,
+            email: listing.email,
+            seller: SellerDetails::from(seller),
+            created_at: listing.created_at,
+            updated_at: listing.updated_at,
+        }
+    }
+}
+
+/// Contact fields default to the seller's own phone and email when omitted.
+#[derive(Deserialize, Validate, Debug)]
+pub struct CreateListing {
+    pub car_id: Uuid,
+    pub seller_id: Uuid,
+    #[validate(range(min = 0))]
+    pub price: i32,
+    pub exchange: bool,
+    #[validate(custom(function = "validate_phone"))]
+    pub phone: Option<String>,
+    #[validate(email)]
+    pub email: Option<String>,
+}
@@ -1,2 +1,10 @@
+pub mod api_key_models;
 pub mod brand_models;
+pub mod car_models;
+pub mod health_models;
+pub mod listing_models;
+pub mod model_models;
+pub mod post_migration_models;
 pub mod post_models;
+pub mod seller_models;
+pub mod version_models;
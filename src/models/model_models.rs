@@ -0,0 +1,53 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::utils::merge_patch::required;
+
+use crate::models::brand_models::Brand;
+
+#[derive(
+    Queryable,
+    Selectable,
+    Serialize,
+    Deserialize,
+    Insertable,
+    Identifiable,
+    Associations,
+    AsChangeset,
+    Debug,
+)]
+#[diesel(belongs_to(Brand))]
+#[diesel(table_name = crate::schema::models)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Model {
+    pub id: Uuid,
+    pub brand_id: Uuid,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub created_by: String,
+    pub updated_by: Option<String>,
+    pub deleted_by: Option<String>,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct CreateModel {
+    pub brand_id: Uuid,
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+}
+
+/// A JSON Merge Patch of a model, only the present fields are changed.
+#[derive(Deserialize, AsChangeset, Validate, Debug)]
+#[diesel(table_name = crate::schema::models)]
+pub struct UpdateModel {
+    #[serde(default, deserialize_with = "required")]
+    pub brand_id: Option<Uuid>,
+    #[serde(default, deserialize_with = "required")]
+    #[validate(length(min = 1, max = 100))]
+    pub name: Option<String>,
+}
@@ -0,0 +1,39 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Records which listing a post was migrated to, so a post is never migrated twice.
+#[derive(Queryable, Selectable, Insertable, Serialize, Deserialize, Debug)]
+#[diesel(table_name = crate::schema::post_listings)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct PostListing {
+    pub post_id: Uuid,
+    pub listing_id: Uuid,
+    pub migrated_at: DateTime<Utc>,
+    pub migrated_by: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct MigratedPost {
+    pub post_id: Uuid,
+    /// The car and listing created for this post, `None` on a dry run as nothing was persisted.
+    pub car_id: Option<Uuid>,
+    pub listing_id: Option<Uuid>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct SkippedPost {
+    pub post_id: Uuid,
+    pub reason: String,
+}
+
+#[derive(Serialize, Debug, Default)]
+pub struct PostMigrationReport {
+    pub dry_run: bool,
+    pub total: usize,
+    pub migrated: Vec<MigratedPost>,
+    pub unmatched: Vec<SkippedPost>,
+    pub ambiguous: Vec<SkippedPost>,
+    pub failed: Vec<SkippedPost>,
+}
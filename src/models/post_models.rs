@@ -1,6 +1,11 @@
+use chrono::{DateTime, Utc};
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
+use validator::Validate;
+
+use crate::utils::merge_patch::required;
+use crate::utils::vehicle_validation::validate_model_year;
 
 #[derive(
     Queryable, Selectable, Serialize, Deserialize, Insertable, Identifiable, AsChangeset, Debug,
@@ -24,41 +29,109 @@ pub struct Post {
     pub thumbnail_url: String,
     pub author: String,
     pub published: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub created_by: String,
+    pub updated_by: Option<String>,
+    pub deleted_by: Option<String>,
 }
 
-#[derive(serde::Deserialize, Debug)]
+#[derive(serde::Deserialize, Validate, Debug)]
 pub struct CreatePost {
+    #[validate(length(min = 1, max = 100))]
     pub brand: String,
+    #[validate(length(min = 1, max = 100))]
     pub model: String,
+    #[validate(length(min = 1, max = 100))]
     pub version: String,
+    #[validate(length(min = 1, max = 100))]
     pub engine: String,
+    #[validate(length(min = 1, max = 100))]
     pub transmission: String,
+    #[validate(custom(function = "validate_model_year"))]
     pub year: i32,
+    #[validate(range(min = 0))]
     pub mileage: i32,
+    #[validate(length(min = 1, max = 100))]
     pub color: String,
+    #[validate(length(min = 1, max = 100))]
     pub body: String,
     pub armored: bool,
     pub exchange: bool,
+    #[validate(range(min = 0))]
     pub price: i64,
+    #[validate(url)]
     pub thumbnail_url: String,
-    pub author: String,
+    /// Defaults to the authenticated user, only admins may post on behalf of someone else.
+    #[validate(length(min = 1, max = 100))]
+    pub author: Option<String>,
 }
 
-#[derive(serde::Deserialize, Debug)]
+/// A JSON Merge Patch of a post, only the present fields are changed.
+#[derive(serde::Deserialize, AsChangeset, Validate, Debug)]
+#[diesel(table_name = crate::schema::posts)]
 pub struct UpdatePost {
-    pub brand: String,
-    pub model: String,
-    pub version: String,
-    pub engine: String,
-    pub transmission: String,
-    pub year: i32,
-    pub mileage: i32,
-    pub color: String,
-    pub body: String,
-    pub armored: bool,
-    pub exchange: bool,
-    pub price: i64,
-    pub thumbnail_url: String,
-    pub author: String,
-    pub published: bool,
+    #[serde(default, deserialize_with = "required")]
+    #[validate(length(min = 1, max = 100))]
+    pub brand: Option<String>,
+    #[serde(default, deserialize_with = "required")]
+    #[validate(length(min = 1, max = 100))]
+    pub model: Option<String>,
+    #[serde(default, deserialize_with = "required")]
+    #[validate(length(min = 1, max = 100))]
+    pub version: Option<String>,
+    #[serde(default, deserialize_with = "required")]
+    #[validate(length(min = 1, max = 100))]
+    pub engine: Option<String>,
+    #[serde(default, deserialize_with = "required")]
+    #[validate(length(min = 1, max = 100))]
+    pub transmission: Option<String>,
+    #[serde(default, deserialize_with = "required")]
+    #[validate(custom(function = "validate_model_year"))]
+    pub year: Option<i32>,
+    #[serde(default, deserialize_with = "required")]
+    #[validate(range(min = 0))]
+    pub mileage: Option<i32>,
+    #[serde(default, deserialize_with = "required")]
+    #[validate(length(min = 1, max = 100))]
+    pub color: Option<String>,
+    #[serde(default, deserialize_with = "required")]
+    #[validate(length(min = 1, max = 100))]
+    pub body: Option<String>,
+    #[serde(default, deserialize_with = "required")]
+    pub armored: Option<bool>,
+    #[serde(default, deserialize_with = "required")]
+    pub exchange: Option<bool>,
+    #[serde(default, deserialize_with = "required")]
+    #[validate(range(min = 0))]
+    pub price: Option<i64>,
+    #[serde(default, deserialize_with = "required")]
+    #[validate(url)]
+    pub thumbnail_url: Option<String>,
+    #[serde(default, deserialize_with = "required")]
+    #[validate(length(min = 1, max = 100))]
+    pub author: Option<String>,
+    #[serde(default, deserialize_with = "required")]
+    pub published: Option<bool>,
+}
+
+impl UpdatePost {
+    pub fn is_empty(&self) -> bool {
+        self.brand.is_none()
+            && self.model.is_none()
+            && self.version.is_none()
+            && self.engine.is_none()
+            && self.transmission.is_none()
+            && self.year.is_none()
+            && self.mileage.is_none()
+            && self.color.is_none()
+            && self.body.is_none()
+            && self.armored.is_none()
+            && self.exchange.is_none()
+            && self.price.is_none()
+            && self.thumbnail_url.is_none()
+            && self.author.is_none()
+            && self.published.is_none()
+    }
 }
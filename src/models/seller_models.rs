@@ -0,0 +1,161 @@
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+use crate::utils::contact_validation::{format_zip_code, validate_phone, validate_zip_code};
+
+#[derive(Queryable, Selectable, Insertable, Identifiable, AsChangeset, Debug)]
+#[diesel(table_name = crate::schema::sellers)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Seller {
+    pub id: Uuid,
+    pub name: String,
+    pub phone: String,
+    pub email: String,
+    pub addr_street: String,
+    pub addr_district: String,
+    pub addr_city: String,
+    pub addr_state: String,
+    pub addr_zip_code: i32,
+    pub start_hour: Option<DateTime<Utc>>,
+    pub end_hour: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub created_by: String,
+    pub updated_by: Option<String>,
+    pub deleted_by: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Validate, Clone, Debug, PartialEq)]
+pub struct Address {
+    #[validate(length(min = 1, max = 100))]
+    pub street: String,
+    #[validate(length(min = 1, max = 100))]
+    pub district: String,
+    #[validate(length(min = 1, max = 100))]
+    pub city: String,
+    #[validate(length(min = 1, max = 100))]
+    pub state: String,
+    #[validate(custom(function = "validate_zip_code"))]
+    pub zip_code: String,
+}
+
+/// Daily opening hours, stored as timestamps on the `start_hour`/`end_hour` columns.
+#[derive(Serialize, Deserialize, Validate, Clone, Copy, Debug, PartialEq)]
+#[validate(schema(function = "validate_business_hours"))]
+pub struct BusinessHours {
+    pub opens: NaiveTime,
+    pub closes: NaiveTime,
+}
+
+impl BusinessHours {
+    pub fn from_columns(
+        start_hour: Option<DateTime<Utc>>,
+        end_hour: Option<DateTime<Utc>>,
+    ) -> Option<BusinessHours> {
+        match (start_hour, end_hour) {
+            (Some(start_hour), Some(end_hour)) => Some(BusinessHours {
+                opens: start_hour.time(),
+                closes: end_hour.time(),
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn to_columns(
+        hours: Option<BusinessHours>,
+    ) -> (Option<DateTime<Utc>>, Option<DateTime<Utc>>) {
+        match hours {
+            Some(hours) => (
+                Some(to_timestamp(hours.opens)),
+                Some(to_timestamp(hours.closes)),
+            ),
+            None => (None, None),
+        }
+    }
+}
+
+fn validate_business_hours(hours: &BusinessHours) -> Result<(), ValidationError> {
+    if hours.opens < hours.closes {
+        Ok(())
+    } else {
+        Err(ValidationError::new("business_hours")
+            .with_message("opens must be earlier than closes".into()))
+    }
+}
+
+fn to_timestamp(time: NaiveTime) -> DateTime<Utc> {
+    NaiveDate::default().and_time(time).and_utc()
+}
+
+#[derive(Serialize, Debug)]
+pub struct SellerDetails {
+    pub id: Uuid,
+    pub name: String,
+    pub phone: String,
+    pub email: String,
+    pub address: Address,
+    pub business_hours: Option<BusinessHours>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub created_by: String,
+    pub updated_by: Option<String>,
+    pub deleted_by: Option<String>,
+}
+
+impl From<Seller> for SellerDetails {
+    fn from(seller: Seller) -> Self {
+        SellerDetails {
+            id: seller.id,
+            name: seller.name,
+            phone: seller.phone,
+            email: seller.email,
+            address: Address {
+                street: seller.addr_street,
+                district: seller.addr_district,
+                city: seller.addr_city,
+                state: seller.addr_state,
+                zip_code: format_zip_code(seller.addr_zip_code),
+            },
+            business_hours: BusinessHours::from_columns(seller.start_hour, seller.end_hour),
+            created_at: seller.created_at,
+            updated_at: seller.updated_at,
+            deleted_at: seller.deleted_at,
+            created_by: seller.created_by,
+            updated_by: seller.updated_by,
+            deleted_by: seller.deleted_by,
+        }
+    }
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct CreateSeller {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    #[validate(custom(function = "validate_phone"))]
+    pub phone: String,
+    #[validate(email)]
+    pub email: String,
+    #[validate(nested)]
+    pub address: Address,
+    #[validate(nested)]
+    pub business_hours: Option<BusinessHours>,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct UpdateSeller {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    #[validate(custom(function = "validate_phone"))]
+    pub phone: String,
+    #[validate(email)]
+    pub email: String,
+    #[validate(nested)]
+    pub address: Address,
+    #[validate(nested)]
+    pub business_hours: Option<BusinessHours>,
+}
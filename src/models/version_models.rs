@@ -0,0 +1,84 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::utils::merge_patch::required;
+use crate::utils::vehicle_validation::validate_model_year;
+
+use crate::models::model_models::Model;
+
+#[derive(
+    Queryable,
+    Selectable,
+    Serialize,
+    Deserialize,
+    Insertable,
+    Identifiable,
+    Associations,
+    AsChangeset,
+    Debug,
+)]
+#[diesel(belongs_to(Model))]
+#[diesel(table_name = crate::schema::versions)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Version {
+    pub id: Uuid,
+    pub model_id: Uuid,
+    pub name: String,
+    pub engine: String,
+    pub transmission: String,
+    pub year: i32,
+    pub body: String,
+    pub doors: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub created_by: String,
+    pub updated_by: Option<String>,
+    pub deleted_by: Option<String>,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct CreateVersion {
+    pub model_id: Uuid,
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    #[validate(length(min = 1, max = 100))]
+    pub engine: String,
+    #[validate(length(min = 1, max = 100))]
+    pub transmission: String,
+    #[validate(custom(function = "validate_model_year"))]
+    pub year: i32,
+    #[validate(length(min = 1, max = 100))]
+    pub body: String,
+    #[validate(range(min = 2, max = 5))]
+    pub doors: i32,
+}
+
+/// A JSON Merge Patch of a version, only the present fields are changed.
+#[derive(Deserialize, AsChangeset, Validate, Debug)]
+#[diesel(table_name = crate::schema::versions)]
+pub struct UpdateVersion {
+    #[serde(default, deserialize_with = "required")]
+    pub model_id: Option<Uuid>,
+    #[serde(default, deserialize_with = "required")]
+    #[validate(length(min = 1, max = 100))]
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "required")]
+    #[validate(length(min = 1, max = 100))]
+    pub engine: Option<String>,
+    #[serde(default, deserialize_with = "required")]
+    #[validate(length(min = 1, max = 100))]
+    pub transmission: Option<String>,
+    #[serde(default, deserialize_with = "required")]
+    #[validate(custom(function = "validate_model_year"))]
+    pub year: Option<i32>,
+    #[serde(default, deserialize_with = "required")]
+    #[validate(length(min = 1, max = 100))]
+    pub body: Option<String>,
+    #[serde(default, deserialize_with = "required")]
+    #[validate(range(min = 2, max = 5))]
+    pub doors: Option<i32>,
+}
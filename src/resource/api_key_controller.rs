@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::middleware;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{delete, get, post};
+use axum::{Json, Router};
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::PgConnection;
+use uuid::Uuid;
+
+use crate::auth::principal::Principal;
+use crate::auth::role::require_admin;
+use crate::models::api_key_models::IssueApiKey;
+use crate::service::api_key_service;
+use crate::utils::blocking::run_blocking;
+use crate::utils::validated_json::ValidatedJson;
+
+pub fn router(pool: Arc<Pool<ConnectionManager<PgConnection>>>) -> Router {
+    Router::new()
+        .route("/v1/admin/api-key", get(self::get_all))
+        .route("/v1/admin/api-key", post(self::issue_one))
+        .route("/v1/admin/api-key/:id", delete(self::revoke_one))
+        .route("/v1/admin/api-key/:id/rotate", post(self::rotate_one))
+        // Every operation is restricted to admins
+        .route_layer(middleware::from_fn(require_admin))
+        // Route state
+        .with_state(pool)
+}
+
+pub async fn get_all(State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>) -> Response {
+    match run_blocking(move || api_key_service::get_api_keys(pool)).await {
+        Ok(api_keys) => (StatusCode::OK, Json(api_keys)).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+pub async fn issue_one(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Principal,
+    ValidatedJson(payload): ValidatedJson<IssueApiKey>,
+) -> Response {
+    match run_blocking(move || api_key_service::issue_api_key(pool, payload, &principal)).await {
+        Ok(api_key) => (StatusCode::CREATED, Json(api_key)).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+pub async fn rotate_one(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Principal,
+    Path(api_key_id): Path<Uuid>,
+) -> Response {
+    match run_blocking(move || api_key_service::rotate_api_key(pool, api_key_id, &principal)).await
+    {
+        Ok(api_key) => (StatusCode::OK, Json(api_key)).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+pub async fn revoke_one(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Principal,
+    Path(api_key_id): Path<Uuid>,
+) -> Response {
+    match run_blocking(move || api_key_service::revoke_api_key(pool, api_key_id, &principal)).await
+    {
+        Ok(count) => get_status_code_for_count(count).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+fn get_status_code_for_count(count: usize) -> StatusCode {
+    if count > 0 {
+        StatusCode::NO_CONTENT
+    } else {
+        StatusCode::NOT_FOUND
+    }
+}
@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
+use axum::middleware;
 use axum::response::{IntoResponse, Response};
 use axum::routing::{delete, get, patch, post};
 use axum::{Json, Router};
@@ -10,8 +11,12 @@ use diesel::PgConnection;
 use serde::Deserialize;
 use uuid::Uuid;
 
+use crate::auth::principal::Principal;
+use crate::auth::role::{authorize, require_admin, require_catalog_editor, Role};
 use crate::models::brand_models::{CreateBrand, UpdateBrand};
 use crate::service::brand_service;
+use crate::utils::blocking::run_blocking;
+use crate::utils::validated_json::ValidatedJson;
 
 const MAX_LIMIT: u32 = 100;
 
@@ -19,13 +24,37 @@ pub fn router(pool: Arc<Pool<ConnectionManager<PgConnection>>>) -> Router {
     Router::new()
         .route("/v1/brand", get(get_all))
         // Single operations
-        .route("/v1/brand", post(self::create_one))
+        .route(
+            "/v1/brand",
+            post(self::create_one).route_layer(middleware::from_fn(require_catalog_editor)),
+        )
         .route("/v1/brand/:id", get(self::get_one))
-        .route("/v1/brand/:id", patch(self::update_one))
-        .route("/v1/brand/:id", delete(self::delete_one))
+        .route(
+            "/v1/brand/:id",
+            patch(self::update_one).route_layer(middleware::from_fn(require_catalog_editor)),
+        )
+        .route(
+            "/v1/brand/:id",
+            delete(self::delete_one).route_layer(middleware::from_fn(require_catalog_editor)),
+        )
+        .route(
+            "/v1/brand/:id/restore",
+            post(self::restore_one).route_layer(middleware::from_fn(require_catalog_editor)),
+        )
         // Bulk operations
-        .route("/v1/brand/bulk", post(self::create_many))
-        .route("/v1/brand/bulk", delete(self::delete_many))
+        .route(
+            "/v1/brand/bulk",
+            post(self::create_many).route_layer(middleware::from_fn(require_catalog_editor)),
+        )
+        .route(
+            "/v1/brand/bulk",
+            delete(self::delete_many).route_layer(middleware::from_fn(require_admin)),
+        )
+        // Admin operations
+        .route(
+            "/v1/admin/brand/:id",
+            delete(self::purge_one).route_layer(middleware::from_fn(require_admin)),
+        )
         .with_state(pool)
 }
 
@@ -37,84 +66,140 @@ pub struct GetParams {
     sort_order: Option<String>,
     filter_by: Option<String>,
     filter_term: Option<String>,
+    include_deleted: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub struct GetOneParams {
+    include_deleted: Option<bool>,
 }
 
 pub async fn get_all(
     State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Option<Principal>,
     Query(params): Query<GetParams>,
 ) -> Response {
-    match brand_service::get_brands(
-        pool,
-        params.offset.unwrap_or(0),
-        params.limit.unwrap_or(10).min(MAX_LIMIT),
-        params.sort_by.unwrap_or_else(|| String::from("name")),
-        params.sort_order.unwrap_or_else(|| String::from("asc")),
-        params.filter_by.unwrap_or_else(|| String::from("")),
-        params.filter_term.unwrap_or_else(|| String::from("")),
-    ) {
+    let include_deleted = params.include_deleted.unwrap_or(false);
+    if include_deleted {
+        if let Err(err) = authorize(principal.as_ref(), &[Role::Admin]) {
+            return err.into_response();
+        }
+    }
+
+    match run_blocking(move || {
+        brand_service::get_brands(
+            pool,
+            params.offset.unwrap_or(0),
+            params.limit.unwrap_or(10).min(MAX_LIMIT),
+            params.sort_by.unwrap_or_else(|| String::from("name")),
+            params.sort_order.unwrap_or_else(|| String::from("asc")),
+            params.filter_by.unwrap_or_else(|| String::from("")),
+            params.filter_term.unwrap_or_else(|| String::from("")),
+            include_deleted,
+        )
+    })
+    .await
+    {
         Ok(brands) => (StatusCode::OK, Json(brands)).into_response(),
-        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+        Err(err) => err.into_response(),
     }
 }
 
 pub async fn get_one(
     State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Option<Principal>,
     Path(brand_id): Path<Uuid>,
+    Query(params): Query<GetOneParams>,
 ) -> Response {
-    match brand_service::get_brand(pool, brand_id) {
+    let include_deleted = params.include_deleted.unwrap_or(false);
+    if include_deleted {
+        if let Err(err) = authorize(principal.as_ref(), &[Role::Admin]) {
+            return err.into_response();
+        }
+    }
+
+    match run_blocking(move || brand_service::get_brand(pool, brand_id, include_deleted)).await {
         Ok(brand) => (StatusCode::OK, Json(brand)).into_response(),
-        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+        Err(err) => err.into_response(),
     }
 }
 
 pub async fn create_one(
     State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
-    Json(payload): Json<CreateBrand>,
+    principal: Principal,
+    ValidatedJson(payload): ValidatedJson<CreateBrand>,
 ) -> Response {
-    match brand_service::create_brand(pool, payload) {
-        Ok(brand) => (StatusCode::OK, Json(brand)).into_response(),
-        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+    match run_blocking(move || brand_service::create_brand(pool, payload, &principal)).await {
+        Ok(brand) => (StatusCode::CREATED, Json(brand)).into_response(),
+        Err(err) => err.into_response(),
     }
 }
 
 pub async fn create_many(
     State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
-    Json(payload): Json<Vec<CreateBrand>>,
+    principal: Principal,
+    ValidatedJson(payload): ValidatedJson<Vec<CreateBrand>>,
 ) -> Response {
-    match brand_service::create_brands(pool, payload) {
-        Ok(brand) => (StatusCode::OK, Json(brand)).into_response(),
-        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+    match run_blocking(move || brand_service::create_brands(pool, payload, &principal)).await {
+        Ok(brands) => (StatusCode::CREATED, Json(brands)).into_response(),
+        Err(err) => err.into_response(),
     }
 }
 
 pub async fn update_one(
     State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Principal,
     Path(brand_id): Path<Uuid>,
-    Json(payload): Json<UpdateBrand>,
+    ValidatedJson(payload): ValidatedJson<UpdateBrand>,
 ) -> Response {
-    match brand_service::update_brand(pool, brand_id, payload) {
+    match run_blocking(move || brand_service::update_brand(pool, brand_id, payload, &principal))
+        .await
+    {
         Ok(brand) => (StatusCode::OK, Json(brand)).into_response(),
-        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+        Err(err) => err.into_response(),
     }
 }
 
 pub async fn delete_one(
     State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Principal,
     Path(brand_id): Path<Uuid>,
 ) -> Response {
-    match brand_service::delete_brand(pool, brand_id) {
+    match run_blocking(move || brand_service::delete_brand(pool, brand_id, &principal)).await {
         Ok(count) => get_status_code_for_count(count).into_response(),
-        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+        Err(err) => err.into_response(),
     }
 }
 
 pub async fn delete_many(
     State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Principal,
     Json(brands_ids): Json<Vec<Uuid>>,
 ) -> Response {
-    match brand_service::delete_brands(pool, brands_ids) {
+    match run_blocking(move || brand_service::delete_brands(pool, brands_ids, &principal)).await {
+        Ok(count) => get_status_code_for_count(count).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+pub async fn restore_one(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Principal,
+    Path(brand_id): Path<Uuid>,
+) -> Response {
+    match run_blocking(move || brand_service::restore_brand(pool, brand_id, &principal)).await {
+        Ok(count) => get_status_code_for_count(count).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+pub async fn purge_one(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    Path(brand_id): Path<Uuid>,
+) -> Response {
+    match run_blocking(move || brand_service::purge_brand(pool, brand_id)).await {
         Ok(count) => get_status_code_for_count(count).into_response(),
-        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+        Err(err) => err.into_response(),
     }
 }
 
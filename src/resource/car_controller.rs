@@ -0,0 +1,212 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::middleware;
+use axum::response::{IntoResponse, Response};
+use axum::routing::delete;
+use axum::routing::{get, patch, post};
+use axum::{Json, Router};
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::PgConnection;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::auth::principal::Principal;
+use crate::auth::role::{authorize, require_admin, require_catalog_editor, require_seller, Role};
+use crate::models::car_models::{CreateCar, UpdateCar};
+use crate::service::car_service;
+use crate::utils::blocking::run_blocking;
+use crate::utils::validated_json::ValidatedJson;
+
+const MAX_LIMIT: u32 = 100;
+
+pub fn router(pool: Arc<Pool<ConnectionManager<PgConnection>>>) -> Router {
+    Router::new()
+        .route("/v1/car", get(get_all))
+        // Single operations
+        .route(
+            "/v1/car",
+            post(self::create_one).route_layer(middleware::from_fn(require_seller)),
+        )
+        .route("/v1/car/:id", get(self::get_one))
+        .route(
+            "/v1/car/:id",
+            delete(self::delete_one).route_layer(middleware::from_fn(require_seller)),
+        )
+        .route(
+            "/v1/car/:id",
+            patch(self::update_one).route_layer(middleware::from_fn(require_seller)),
+        )
+        .route(
+            "/v1/car/:id/restore",
+            post(self::restore_one).route_layer(middleware::from_fn(require_catalog_editor)),
+        )
+        // Bulk operations
+        .route(
+            "/v1/car/bulk",
+            post(self::create_many).route_layer(middleware::from_fn(require_seller)),
+        )
+        .route(
+            "/v1/car/bulk",
+            delete(self::delete_many).route_layer(middleware::from_fn(require_admin)),
+        )
+        // Admin operations
+        .route(
+            "/v1/admin/car/:id",
+            delete(self::purge_one).route_layer(middleware::from_fn(require_admin)),
+        )
+        // Route state
+        .with_state(pool)
+}
+
+#[derive(Deserialize)]
+pub struct GetParams {
+    offset: Option<u32>,
+    limit: Option<u32>,
+    sort_by: Option<String>,
+    sort_order: Option<String>,
+    filter_by: Option<String>,
+    filter_term: Option<String>,
+    include_deleted: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub struct GetOneParams {
+    include_deleted: Option<bool>,
+}
+
+pub async fn get_all(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Option<Principal>,
+    Query(params): Query<GetParams>,
+) -> Response {
+    let include_deleted = params.include_deleted.unwrap_or(false);
+    if include_deleted {
+        if let Err(err) = authorize(principal.as_ref(), &[Role::Admin]) {
+            return err.into_response();
+        }
+    }
+
+    match run_blocking(move || {
+        car_service::get_cars(
+            pool,
+            params.offset.unwrap_or(0),
+            params.limit.unwrap_or(10).min(MAX_LIMIT),
+            params.sort_by.unwrap_or_else(|| String::from("mileage")),
+            params.sort_order.unwrap_or_else(|| String::from("asc")),
+            params.filter_by.unwrap_or_else(|| String::from("")),
+            params.filter_term.unwrap_or_else(|| String::from("")),
+            include_deleted,
+        )
+    })
+    .await
+    {
+        Ok(cars) => (StatusCode::OK, Json(cars)).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+pub async fn get_one(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Option<Principal>,
+    Path(car_id): Path<Uuid>,
+    Query(params): Query<GetOneParams>,
+) -> Response {
+    let include_deleted = params.include_deleted.unwrap_or(false);
+    if include_deleted {
+        if let Err(err) = authorize(principal.as_ref(), &[Role::Admin]) {
+            return err.into_response();
+        }
+    }
+
+    match run_blocking(move || car_service::get_car(pool, car_id, include_deleted)).await {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+pub async fn create_one(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Principal,
+    ValidatedJson(payload): ValidatedJson<CreateCar>,
+) -> Response {
+    match run_blocking(move || car_service::create_car(pool, payload, &principal)).await {
+        Ok(car) => (StatusCode::CREATED, Json(car)).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+pub async fn create_many(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Principal,
+    ValidatedJson(payload): ValidatedJson<Vec<CreateCar>>,
+) -> Response {
+    match run_blocking(move || car_service::create_cars(pool, payload, &principal)).await {
+        Ok(cars) => (StatusCode::CREATED, Json(cars)).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+pub async fn update_one(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Principal,
+    Path(car_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<UpdateCar>,
+) -> Response {
+    match run_blocking(move || car_service::update_car(pool, car_id, payload, &principal)).await {
+        Ok(count) => get_status_code_for_count(count).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+pub async fn delete_one(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Principal,
+    Path(car_id): Path<Uuid>,
+) -> Response {
+    match run_blocking(move || car_service::delete_car(pool, car_id, &principal)).await {
+        Ok(count) => get_status_code_for_count(count).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+pub async fn delete_many(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Principal,
+    Json(cars_ids): Json<Vec<Uuid>>,
+) -> Response {
+    match run_blocking(move || car_service::delete_cars(pool, cars_ids, &principal)).await {
+        Ok(count) => get_status_code_for_count(count).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+pub async fn restore_one(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Principal,
+    Path(car_id): Path<Uuid>,
+) -> Response {
+    match run_blocking(move || car_service::restore_car(pool, car_id, &principal)).await {
+        Ok(count) => get_status_code_for_count(count).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+pub async fn purge_one(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    Path(car_id): Path<Uuid>,
+) -> Response {
+    match run_blocking(move || car_service::purge_car(pool, car_id)).await {
+        Ok(count) => get_status_code_for_count(count).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+fn get_status_code_for_count(count: usize) -> StatusCode {
+    if count > 0 {
+        StatusCode::NO_CONTENT
+    } else {
+        StatusCode::NOT_FOUND
+    }
+}
@@ -0,0 +1,26 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::middleware;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::PgConnection;
+
+use crate::auth::role::require_admin;
+use crate::database::database::pool_status;
+
+pub fn router(pool: Arc<Pool<ConnectionManager<PgConnection>>>) -> Router {
+    Router::new()
+        .route(
+            "/v1/admin/database/pool",
+            get(self::get_pool).route_layer(middleware::from_fn(require_admin)),
+        )
+        .with_state(pool)
+}
+
+pub async fn get_pool(State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>) -> Response {
+    (StatusCode::OK, Json(pool_status(&pool))).into_response()
+}
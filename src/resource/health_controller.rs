@@ -0,0 +1,41 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::PgConnection;
+
+use crate::models::health_models::{Health, HealthStatus};
+use crate::service::health_service;
+use crate::utils::blocking::run_blocking;
+
+pub fn router(pool: Arc<Pool<ConnectionManager<PgConnection>>>) -> Router {
+    Router::new()
+        .route("/health/live", get(self::live))
+        .route("/health/ready", get(self::ready))
+        .with_state(pool)
+}
+
+/// The process is up and serving requests.
+pub async fn live() -> Response {
+    (
+        StatusCode::OK,
+        Json(Health::from_components(BTreeMap::new())),
+    )
+        .into_response()
+}
+
+/// The database is reachable and up to date, 503 otherwise.
+pub async fn ready(State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>) -> Response {
+    match run_blocking(move || Ok(health_service::check_readiness(pool))).await {
+        Ok(health) if health.status == HealthStatus::Up => {
+            (StatusCode::OK, Json(health)).into_response()
+        }
+        Ok(health) => (StatusCode::SERVICE_UNAVAILABLE, Json(health)).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
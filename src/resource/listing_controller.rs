@@ -0,0 +1,168 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::middleware;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{delete, get, post};
+use axum::{Json, Router};
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::PgConnection;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::auth::principal::Principal;
+use crate::auth::role::{authorize, require_admin, require_catalog_editor, require_seller, Role};
+use crate::models::listing_models::CreateListing;
+use crate::service::listing_service;
+use crate::utils::blocking::run_blocking;
+use crate::utils::validated_json::ValidatedJson;
+
+const MAX_LIMIT: u32 = 100;
+
+pub fn router(pool: Arc<Pool<ConnectionManager<PgConnection>>>) -> Router {
+    Router::new()
+        .route("/v1/listing", get(get_all))
+        // Single operations
+        .route(
+            "/v1/listing",
+            post(self::create_one).route_layer(middleware::from_fn(require_seller)),
+        )
+        .route("/v1/listing/:id", get(self::get_one))
+        .route(
+            "/v1/listing/:id",
+            delete(self::delete_one).route_layer(middleware::from_fn(require_catalog_editor)),
+        )
+        .route(
+            "/v1/listing/:id/restore",
+            post(self::restore_one).route_layer(middleware::from_fn(require_catalog_editor)),
+        )
+        // Admin operations
+        .route(
+            "/v1/admin/listing/:id",
+            delete(self::purge_one).route_layer(middleware::from_fn(require_admin)),
+        )
+        // Route state
+        .with_state(pool)
+}
+
+#[derive(Deserialize)]
+pub struct GetParams {
+    offset: Option<u32>,
+    limit: Option<u32>,
+    sort_by: Option<String>,
+    sort_order: Option<String>,
+    filter_by: Option<String>,
+    filter_term: Option<String>,
+    include_deleted: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub struct GetOneParams {
+    include_deleted: Option<bool>,
+}
+
+pub async fn get_all(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Option<Principal>,
+    Query(params): Query<GetParams>,
+) -> Response {
+    let include_deleted = params.include_deleted.unwrap_or(false);
+    if include_deleted {
+        if let Err(err) = authorize(principal.as_ref(), &[Role::Admin]) {
+            return err.into_response();
+        }
+    }
+
+    match run_blocking(move || {
+        listing_service::get_listings(
+            pool,
+            params.offset.unwrap_or(0),
+            params.limit.unwrap_or(10).min(MAX_LIMIT),
+            params.sort_by.unwrap_or_else(|| String::from("model")),
+            params.sort_order.unwrap_or_else(|| String::from("asc")),
+            params.filter_by.unwrap_or_else(|| String::from("")),
+            params.filter_term.unwrap_or_else(|| String::from("")),
+            include_deleted,
+        )
+    })
+    .await
+    {
+        Ok(listings) => (StatusCode::OK, Json(listings)).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+pub async fn get_one(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Option<Principal>,
+    Path(listing_id): Path<Uuid>,
+    Query(params): Query<GetOneParams>,
+) -> Response {
+    let include_deleted = params.include_deleted.unwrap_or(false);
+    if include_deleted {
+        if let Err(err) = authorize(principal.as_ref(), &[Role::Admin]) {
+            return err.into_response();
+        }
+    }
+
+    match run_blocking(move || listing_service::get_listing(pool, listing_id, include_deleted))
+        .await
+    {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+pub async fn create_one(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Principal,
+    ValidatedJson(payload): ValidatedJson<CreateListing>,
+) -> Response {
+    match run_blocking(move || listing_service::create_listing(pool, payload, &principal)).await {
+        Ok(listing) => (StatusCode::CREATED, Json(listing)).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+pub async fn delete_one(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Principal,
+    Path(listing_id): Path<Uuid>,
+) -> Response {
+    match run_blocking(move || listing_service::delete_listing(pool, listing_id, &principal)).await
+    {
+        Ok(count) => get_status_code_for_count(count).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+pub async fn restore_one(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Principal,
+    Path(listing_id): Path<Uuid>,
+) -> Response {
+    match run_blocking(move || listing_service::restore_listing(pool, listing_id, &principal)).await
+    {
+        Ok(count) => get_status_code_for_count(count).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+pub async fn purge_one(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    Path(listing_id): Path<Uuid>,
+) -> Response {
+    match run_blocking(move || listing_service::purge_listing(pool, listing_id)).await {
+        Ok(count) => get_status_code_for_count(count).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+fn get_status_code_for_count(count: usize) -> StatusCode {
+    if count > 0 {
+        StatusCode::NO_CONTENT
+    } else {
+        StatusCode::NOT_FOUND
+    }
+}
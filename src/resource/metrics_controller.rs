@@ -0,0 +1,61 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::State;
+use axum::http::header::CONTENT_TYPE;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::PgConnection;
+use log::{error, warn};
+
+use crate::database::database::pool_status;
+use crate::metrics::registry::metrics;
+use crate::service::post_service;
+use crate::utils::blocking::run_blocking;
+
+/// How often the published posts gauge is recounted, so scrapes never reach the database.
+const PUBLISHED_POSTS_REFRESH: Duration = Duration::from_secs(30);
+
+pub fn router(pool: Arc<Pool<ConnectionManager<PgConnection>>>) -> Router {
+    Router::new()
+        .route("/metrics", get(self::get_metrics))
+        .with_state(pool)
+}
+
+/// Recounts the published posts every `PUBLISHED_POSTS_REFRESH` for as long as the server runs.
+pub async fn refresh_published_posts(pool: Arc<Pool<ConnectionManager<PgConnection>>>) {
+    let mut interval = tokio::time::interval(PUBLISHED_POSTS_REFRESH);
+    loop {
+        interval.tick().await;
+        let pool = pool.clone();
+        match run_blocking(move || post_service::count_published_posts(pool)).await {
+            Ok(count) => metrics().published_posts.set(count),
+            Err(err) => warn!("Unable to refresh published posts gauge, error: {}", err),
+        }
+    }
+}
+
+/// The pool gauge is read on scrape, the published posts one is kept by
+/// `refresh_published_posts`, counters and histograms are updated as requests come in.
+pub async fn get_metrics(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+) -> Response {
+    let metrics = metrics();
+    metrics.observe_pool(&pool_status(&pool));
+
+    match metrics.render() {
+        Ok(body) => (
+            StatusCode::OK,
+            [(CONTENT_TYPE, "text/plain; version=0.0.4")],
+            body,
+        )
+            .into_response(),
+        Err(err) => {
+            error!("Unable to render metrics, error: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
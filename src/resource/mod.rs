@@ -1,2 +1,12 @@
+pub mod api_key_controller;
 pub mod brand_controller;
+pub mod car_controller;
+pub mod database_controller;
+pub mod health_controller;
+pub mod listing_controller;
+pub mod metrics_controller;
+pub mod model_controller;
 pub mod post_controller;
+pub mod post_migration_controller;
+pub mod seller_controller;
+pub mod version_controller;
@@ -0,0 +1,232 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::middleware;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{delete, get, patch, post};
+use axum::{Json, Router};
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::PgConnection;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::auth::principal::Principal;
+use crate::auth::role::{authorize, require_admin, require_catalog_editor, Role};
+use crate::models::model_models::{CreateModel, UpdateModel};
+use crate::service::model_service;
+use crate::utils::blocking::run_blocking;
+use crate::utils::validated_json::ValidatedJson;
+
+const MAX_LIMIT: u32 = 100;
+
+pub fn router(pool: Arc<Pool<ConnectionManager<PgConnection>>>) -> Router {
+    Router::new()
+        .route("/v1/model", get(get_all))
+        .route("/v1/brand/:id/models", get(get_all_by_brand))
+        // Single operations
+        .route(
+            "/v1/model",
+            post(self::create_one).route_layer(middleware::from_fn(require_catalog_editor)),
+        )
+        .route("/v1/model/:id", get(self::get_one))
+        .route(
+            "/v1/model/:id",
+            patch(self::update_one).route_layer(middleware::from_fn(require_catalog_editor)),
+        )
+        .route(
+            "/v1/model/:id",
+            delete(self::delete_one).route_layer(middleware::from_fn(require_catalog_editor)),
+        )
+        .route(
+            "/v1/model/:id/restore",
+            post(self::restore_one).route_layer(middleware::from_fn(require_catalog_editor)),
+        )
+        // Bulk operations
+        .route(
+            "/v1/model/bulk",
+            post(self::create_many).route_layer(middleware::from_fn(require_catalog_editor)),
+        )
+        .route(
+            "/v1/model/bulk",
+            delete(self::delete_many).route_layer(middleware::from_fn(require_admin)),
+        )
+        // Admin operations
+        .route(
+            "/v1/admin/model/:id",
+            delete(self::purge_one).route_layer(middleware::from_fn(require_admin)),
+        )
+        .with_state(pool)
+}
+
+#[derive(Deserialize)]
+pub struct GetParams {
+    offset: Option<u32>,
+    limit: Option<u32>,
+    sort_by: Option<String>,
+    sort_order: Option<String>,
+    filter_by: Option<String>,
+    filter_term: Option<String>,
+    include_deleted: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub struct GetOneParams {
+    include_deleted: Option<bool>,
+}
+
+pub async fn get_all(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Option<Principal>,
+    Query(params): Query<GetParams>,
+) -> Response {
+    list_models(pool, principal, None, params).await
+}
+
+pub async fn get_all_by_brand(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Option<Principal>,
+    Path(brand_id): Path<Uuid>,
+    Query(params): Query<GetParams>,
+) -> Response {
+    list_models(pool, principal, Some(brand_id), params).await
+}
+
+pub async fn get_one(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Option<Principal>,
+    Path(model_id): Path<Uuid>,
+    Query(params): Query<GetOneParams>,
+) -> Response {
+    let include_deleted = params.include_deleted.unwrap_or(false);
+    if include_deleted {
+        if let Err(err) = authorize(principal.as_ref(), &[Role::Admin]) {
+            return err.into_response();
+        }
+    }
+
+    match run_blocking(move || model_service::get_model(pool, model_id, include_deleted)).await {
+        Ok(model) => (StatusCode::OK, Json(model)).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+pub async fn create_one(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Principal,
+    ValidatedJson(payload): ValidatedJson<CreateModel>,
+) -> Response {
+    match run_blocking(move || model_service::create_model(pool, payload, &principal)).await {
+        Ok(model) => (StatusCode::CREATED, Json(model)).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+pub async fn create_many(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Principal,
+    ValidatedJson(payload): ValidatedJson<Vec<CreateModel>>,
+) -> Response {
+    match run_blocking(move || model_service::create_models(pool, payload, &principal)).await {
+        Ok(models) => (StatusCode::CREATED, Json(models)).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+pub async fn update_one(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Principal,
+    Path(model_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<UpdateModel>,
+) -> Response {
+    match run_blocking(move || model_service::update_model(pool, model_id, payload, &principal))
+        .await
+    {
+        Ok(count) => get_status_code_for_count(count).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+pub async fn delete_one(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Principal,
+    Path(model_id): Path<Uuid>,
+) -> Response {
+    match run_blocking(move || model_service::delete_model(pool, model_id, &principal)).await {
+        Ok(count) => get_status_code_for_count(count).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+pub async fn delete_many(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Principal,
+    Json(models_ids): Json<Vec<Uuid>>,
+) -> Response {
+    match run_blocking(move || model_service::delete_models(pool, models_ids, &principal)).await {
+        Ok(count) => get_status_code_for_count(count).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+pub async fn restore_one(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Principal,
+    Path(model_id): Path<Uuid>,
+) -> Response {
+    match run_blocking(move || model_service::restore_model(pool, model_id, &principal)).await {
+        Ok(count) => get_status_code_for_count(count).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+pub async fn purge_one(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    Path(model_id): Path<Uuid>,
+) -> Response {
+    match run_blocking(move || model_service::purge_model(pool, model_id)).await {
+        Ok(count) => get_status_code_for_count(count).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+async fn list_models(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    principal: Option<Principal>,
+    brand_id: Option<Uuid>,
+    params: GetParams,
+) -> Response {
+    let include_deleted = params.include_deleted.unwrap_or(false);
+    if include_deleted {
+        if let Err(err) = authorize(principal.as_ref(), &[Role::Admin]) {
+            return err.into_response();
+        }
+    }
+
+    match run_blocking(move || {
+        model_service::get_models(
+            pool,
+            brand_id,
+            params.offset.unwrap_or(0),
+            params.limit.unwrap_or(10).min(MAX_LIMIT),
+            params.sort_by.unwrap_or_else(|| String::from("name")),
+            params.sort_order.unwrap_or_else(|| String::from("asc")),
+            params.filter_by.unwrap_or_else(|| String::from("")),
+            params.filter_term.unwrap_or_else(|| String::from("")),
+            include_deleted,
+        )
+    })
+    .await
+    {
+        Ok(models) => (StatusCode::OK, Json(models)).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+fn get_status_code_for_count(count: usize) -> StatusCode {
+    if count > 0 {
+        StatusCode::NO_CONTENT
+    } else {
+        StatusCode::NOT_FOUND
+    }
+}
@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
+use axum::middleware;
 use axum::response::{IntoResponse, Response};
 use axum::routing::delete;
 use axum::routing::{get, patch, post};
@@ -11,8 +12,12 @@ use diesel::PgConnection;
 use serde::Deserialize;
 use uuid::Uuid;
 
+use crate::auth::principal::Principal;
+use crate::auth::role::require_seller;
 use crate::models::post_models::{CreatePost, UpdatePost};
 use crate::service::post_service;
+use crate::utils::blocking::run_blocking;
+use crate::utils::validated_json::ValidatedJson;
 
 const MAX_LIMIT: u32 = 100;
 
@@ -20,13 +25,28 @@ pub fn router(pool: Arc<Pool<ConnectionManager<PgConnection>>>) -> Router {
     Router::new()
         .route("/v1/post", get(get_all))
         // Single operations
-        .route("/v1/post", post(self::create_one))
+        .route(
+            "/v1/post",
+            post(self::create_one).route_layer(middleware::from_fn(require_seller)),
+        )
         .route("/v1/post/:id", get(self::get_one))
-        .route("/v1/post/:id", delete(self::delete_one))
-        .route("/v1/post/:id", patch(self::update_one))
+        .route(
+            "/v1/post/:id",
+            delete(self::delete_one).route_layer(middleware::from_fn(require_seller)),
+        )
+        .route(
+            "/v1/post/:id",
+            patch(self::update_one).route_layer(middleware::from_fn(require_seller)),
+        )
         // Bulk operations
-        .route("/v1/post/bulk", post(self::create_many))
-        .route("/v1/post/bulk", delete(self::delete_many))
+        .route(
+            "/v1/post/bulk",
+            post(self::create_many).route_layer(middleware::from_fn(require_seller)),
+        )
+        .route(
+            "/v1/post/bulk",
+            delete(self::delete_many).route_layer(middleware::from_fn(require_seller)),
+        )
         // Route state
         .with_state(pool)
 }
@@ -45,78 +65,89 @@ pub async fn get_all(
     State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
     Query(params): Query<GetParams>,
 ) -> Response {
-    match post_service::get_posts(
-        pool,
-        params.offset.unwrap_or(0),
-        params.limit.unwrap_or(10).min(MAX_LIMIT),
-        params.sort_by.unwrap_or_else(|| String::from("model")),
-        params.sort_order.unwrap_or_else(|| String::from("asc")),
-        params.filter_by.unwrap_or_else(|| String::from("")),
-        params.filter_term.unwrap_or_else(|| String::from("")),
-    ) {
+    match run_blocking(move || {
+        post_service::get_posts(
+            pool,
+            params.offset.unwrap_or(0),
+            params.limit.unwrap_or(10).min(MAX_LIMIT),
+            params.sort_by.unwrap_or_else(|| String::from("model")),
+            params.sort_order.unwrap_or_else(|| String::from("asc")),
+            params.filter_by.unwrap_or_else(|| String::from("")),
+            params.filter_term.unwrap_or_else(|| String::from("")),
+        )
+    })
+    .await
+    {
         Ok(posts) => (StatusCode::OK, Json(posts)).into_response(),
-        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+        Err(err) => err.into_response(),
     }
 }
 
 pub async fn get_one(
     State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Option<Principal>,
     Path(post_id): Path<Uuid>,
 ) -> Response {
-    match post_service::get_post(pool, post_id) {
+    match run_blocking(move || post_service::get_post(pool, post_id, principal.as_ref())).await {
         Ok(result) => (StatusCode::OK, Json(result)).into_response(),
-        Err(_) => StatusCode::NOT_FOUND.into_response(),
+        Err(err) => err.into_response(),
     }
 }
 
 pub async fn create_one(
     State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
-    Json(payload): Json<CreatePost>,
+    principal: Principal,
+    ValidatedJson(payload): ValidatedJson<CreatePost>,
 ) -> Response {
-    match post_service::create_post(pool, payload) {
+    match run_blocking(move || post_service::create_post(pool, payload, &principal)).await {
         Ok(post) => (StatusCode::CREATED, Json(post)).into_response(),
-        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+        Err(err) => err.into_response(),
     }
 }
 
 pub async fn create_many(
     State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
-    Json(payload): Json<Vec<CreatePost>>,
+    principal: Principal,
+    ValidatedJson(payload): ValidatedJson<Vec<CreatePost>>,
 ) -> Response {
-    match post_service::create_posts(pool, payload) {
+    match run_blocking(move || post_service::create_posts(pool, payload, &principal)).await {
         Ok(posts) => (StatusCode::CREATED, Json(posts)).into_response(),
-        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+        Err(err) => err.into_response(),
     }
 }
 
 pub async fn update_one(
     State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Principal,
     Path(post_id): Path<Uuid>,
-    Json(payload): Json<UpdatePost>,
+    ValidatedJson(payload): ValidatedJson<UpdatePost>,
 ) -> Response {
-    match post_service::update_post(pool, post_id, payload) {
+    match run_blocking(move || post_service::update_post(pool, post_id, payload, &principal)).await
+    {
         Ok(count) => get_status_code_for_count(count).into_response(),
-        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+        Err(err) => err.into_response(),
     }
 }
 
 pub async fn delete_one(
     State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Principal,
     Path(post_id): Path<Uuid>,
 ) -> Response {
-    match post_service::delete_post(pool, post_id) {
+    match run_blocking(move || post_service::delete_post(pool, post_id, &principal)).await {
         Ok(count) => get_status_code_for_count(count).into_response(),
-        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+        Err(err) => err.into_response(),
     }
 }
 
 pub async fn delete_many(
     State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Principal,
     Json(posts_ids): Json<Vec<Uuid>>,
 ) -> Response {
-    match post_service::delete_posts(pool, posts_ids) {
+    match run_blocking(move || post_service::delete_posts(pool, posts_ids, &principal)).await {
         Ok(count) => get_status_code_for_count(count).into_response(),
-        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+        Err(err) => err.into_response(),
     }
 }
 
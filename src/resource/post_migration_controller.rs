@@ -0,0 +1,53 @@
+use std::sync::Arc;
+
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::middleware;
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Json, Router};
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::PgConnection;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::auth::principal::Principal;
+use crate::auth::role::require_admin;
+use crate::service::post_migration_service;
+use crate::utils::blocking::run_blocking;
+
+pub fn router(pool: Arc<Pool<ConnectionManager<PgConnection>>>) -> Router {
+    Router::new()
+        .route(
+            "/v1/admin/post/migrate",
+            post(self::migrate).route_layer(middleware::from_fn(require_admin)),
+        )
+        // Route state
+        .with_state(pool)
+}
+
+#[derive(Deserialize)]
+pub struct MigrateParams {
+    dry_run: Option<bool>,
+    default_seller_id: Option<Uuid>,
+}
+
+pub async fn migrate(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Principal,
+    Query(params): Query<MigrateParams>,
+) -> Response {
+    match run_blocking(move || {
+        post_migration_service::migrate_posts(
+            pool,
+            params.dry_run.unwrap_or(true),
+            params.default_seller_id,
+            &principal,
+        )
+    })
+    .await
+    {
+        Ok(report) => (StatusCode::OK, Json(report)).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
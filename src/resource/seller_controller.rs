@@ -0,0 +1,215 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::middleware;
+use axum::response::{IntoResponse, Response};
+use axum::routing::delete;
+use axum::routing::{get, patch, post};
+use axum::{Json, Router};
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::PgConnection;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::auth::principal::Principal;
+use crate::auth::role::{authorize, require_admin, require_catalog_editor, require_seller, Role};
+use crate::models::seller_models::{CreateSeller, UpdateSeller};
+use crate::service::seller_service;
+use crate::utils::blocking::run_blocking;
+use crate::utils::validated_json::ValidatedJson;
+
+const MAX_LIMIT: u32 = 100;
+
+pub fn router(pool: Arc<Pool<ConnectionManager<PgConnection>>>) -> Router {
+    Router::new()
+        .route("/v1/seller", get(get_all))
+        // Single operations
+        .route(
+            "/v1/seller",
+            post(self::create_one).route_layer(middleware::from_fn(require_seller)),
+        )
+        .route("/v1/seller/:id", get(self::get_one))
+        .route(
+            "/v1/seller/:id",
+            delete(self::delete_one).route_layer(middleware::from_fn(require_admin)),
+        )
+        .route(
+            "/v1/seller/:id",
+            patch(self::update_one).route_layer(middleware::from_fn(require_seller)),
+        )
+        .route(
+            "/v1/seller/:id/restore",
+            post(self::restore_one).route_layer(middleware::from_fn(require_catalog_editor)),
+        )
+        // Bulk operations
+        .route(
+            "/v1/seller/bulk",
+            post(self::create_many).route_layer(middleware::from_fn(require_admin)),
+        )
+        .route(
+            "/v1/seller/bulk",
+            delete(self::delete_many).route_layer(middleware::from_fn(require_admin)),
+        )
+        // Admin operations
+        .route(
+            "/v1/admin/seller/:id",
+            delete(self::purge_one).route_layer(middleware::from_fn(require_admin)),
+        )
+        // Route state
+        .with_state(pool)
+}
+
+#[derive(Deserialize)]
+pub struct GetParams {
+    offset: Option<u32>,
+    limit: Option<u32>,
+    sort_by: Option<String>,
+    sort_order: Option<String>,
+    filter_by: Option<String>,
+    filter_term: Option<String>,
+    include_deleted: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub struct GetOneParams {
+    include_deleted: Option<bool>,
+}
+
+pub async fn get_all(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Option<Principal>,
+    Query(params): Query<GetParams>,
+) -> Response {
+    let include_deleted = params.include_deleted.unwrap_or(false);
+    if include_deleted {
+        if let Err(err) = authorize(principal.as_ref(), &[Role::Admin]) {
+            return err.into_response();
+        }
+    }
+
+    match run_blocking(move || {
+        seller_service::get_sellers(
+            pool,
+            params.offset.unwrap_or(0),
+            params.limit.unwrap_or(10).min(MAX_LIMIT),
+            params.sort_by.unwrap_or_else(|| String::from("name")),
+            params.sort_order.unwrap_or_else(|| String::from("asc")),
+            params.filter_by.unwrap_or_else(|| String::from("")),
+            params.filter_term.unwrap_or_else(|| String::from("")),
+            include_deleted,
+        )
+    })
+    .await
+    {
+        Ok(sellers) => (StatusCode::OK, Json(sellers)).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+pub async fn get_one(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Option<Principal>,
+    Path(seller_id): Path<Uuid>,
+    Query(params): Query<GetOneParams>,
+) -> Response {
+    let include_deleted = params.include_deleted.unwrap_or(false);
+    if include_deleted {
+        if let Err(err) = authorize(principal.as_ref(), &[Role::Admin]) {
+            return err.into_response();
+        }
+    }
+
+    match run_blocking(move || seller_service::get_seller(pool, seller_id, include_deleted)).await {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+pub async fn create_one(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Principal,
+    ValidatedJson(payload): ValidatedJson<CreateSeller>,
+) -> Response {
+    match run_blocking(move || seller_service::create_seller(pool, payload, &principal)).await {
+        Ok(seller) => (StatusCode::CREATED, Json(seller)).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+pub async fn create_many(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Principal,
+    ValidatedJson(payload): ValidatedJson<Vec<CreateSeller>>,
+) -> Response {
+    match run_blocking(move || seller_service::create_sellers(pool, payload, &principal)).await {
+        Ok(sellers) => (StatusCode::CREATED, Json(sellers)).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+pub async fn update_one(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Principal,
+    Path(seller_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<UpdateSeller>,
+) -> Response {
+    match run_blocking(move || seller_service::update_seller(pool, seller_id, payload, &principal))
+        .await
+    {
+        Ok(count) => get_status_code_for_count(count).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+pub async fn delete_one(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Principal,
+    Path(seller_id): Path<Uuid>,
+) -> Response {
+    match run_blocking(move || seller_service::delete_seller(pool, seller_id, &principal)).await {
+        Ok(count) => get_status_code_for_count(count).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+pub async fn delete_many(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Principal,
+    Json(sellers_ids): Json<Vec<Uuid>>,
+) -> Response {
+    match run_blocking(move || seller_service::delete_sellers(pool, sellers_ids, &principal)).await
+    {
+        Ok(count) => get_status_code_for_count(count).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+pub async fn restore_one(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    principal: Principal,
+    Path(seller_id): Path<Uuid>,
+) -> Response {
+    match run_blocking(move || seller_service::restore_seller(pool, seller_id, &principal)).await {
+        Ok(count) => get_status_code_for_count(count).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+pub async fn purge_one(
+    State(pool): State<Arc<Pool<ConnectionManager<PgConnection>>>>,
+    Path(seller_id): Path<Uuid>,
+) -> Response {
+    match run_blocking(move || seller_service::purge_seller(pool, seller_id)).await {
+        Ok(count) => get_status_code_for_count(count).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+fn get_status_code_for_count(count: usize) -> StatusCode {
+    if count > 0 {
+        StatusCode::NO_CONTENT
+    } else {
+        StatusCode::NOT_FOUND
+    }
+}
@@ -1,5 +1,24 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    api_keys (id) {
+        id -> Uuid,
+        name -> Varchar,
+        owner -> Varchar,
+        key_prefix -> Varchar,
+        key_hash -> Varchar,
+        scopes -> Array<Nullable<Varchar>>,
+        expires_at -> Nullable<Timestamptz>,
+        last_used_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+        revoked_at -> Nullable<Timestamptz>,
+        created_by -> Varchar,
+        updated_by -> Nullable<Varchar>,
+        revoked_by -> Nullable<Varchar>,
+    }
+}
+
 diesel::table! {
     brands (id) {
         id -> Uuid,
@@ -64,6 +83,15 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    post_listings (post_id) {
+        post_id -> Uuid,
+        listing_id -> Uuid,
+        migrated_at -> Timestamptz,
+        migrated_by -> Varchar,
+    }
+}
+
 diesel::table! {
     posts (id) {
         id -> Uuid,
@@ -82,6 +110,12 @@ diesel::table! {
         thumbnail_url -> Varchar,
         author -> Varchar,
         published -> Bool,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+        deleted_at -> Nullable<Timestamptz>,
+        created_by -> Varchar,
+        updated_by -> Nullable<Varchar>,
+        deleted_by -> Nullable<Varchar>,
     }
 }
 
@@ -130,13 +164,17 @@ diesel::joinable!(cars -> versions (version_id));
 diesel::joinable!(listings -> cars (car_id));
 diesel::joinable!(listings -> sellers (seller_id));
 diesel::joinable!(models -> brands (brand_id));
+diesel::joinable!(post_listings -> listings (listing_id));
+diesel::joinable!(post_listings -> posts (post_id));
 diesel::joinable!(versions -> models (model_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    api_keys,
     brands,
     cars,
     listings,
     models,
+    post_listings,
     posts,
     sellers,
     versions,
@@ -0,0 +1,208 @@
+use chrono::{DateTime, Utc};
+
+use crate::auth::api_key::{generate_key, hash_key};
+use crate::auth::principal::Principal;
+use crate::auth::role::Role;
+use crate::database::database::checkout;
+use crate::error::app_error::AppError;
+use crate::models::api_key_models::{ApiKey, IssueApiKey, IssuedApiKey};
+use crate::schema::api_keys::{self, dsl::*};
+
+use diesel::r2d2::{ConnectionManager, Pool, PooledConnection};
+use diesel::{
+    BoolExpressionMethods, ExpressionMethods, OptionalExtension, PgConnection, QueryDsl,
+    RunQueryDsl, SelectableHelper,
+};
+use log::{error, info, warn};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub fn get_api_keys(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+) -> Result<Vec<ApiKey>, AppError> {
+    info!("Get all API keys");
+
+    let result = api_keys::table
+        .select(ApiKey::as_select())
+        .order_by(created_at.desc())
+        .load(&mut get_connection(&pool)?);
+
+    match result {
+        Ok(result) => Ok(result),
+        Err(err) => {
+            error!("Unable to retrieve API keys, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+pub fn issue_api_key(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    issue_api_key: IssueApiKey,
+    principal: &Principal,
+) -> Result<IssuedApiKey, AppError> {
+    info!("Issue API key: {:?}", issue_api_key);
+
+    ensure_scopes_exist(&issue_api_key.scopes)?;
+
+    let generated = generate_key();
+    let new_api_key = ApiKey {
+        id: Uuid::new_v4(),
+        name: issue_api_key.name,
+        owner: issue_api_key.owner,
+        key_prefix: generated.prefix,
+        key_hash: generated.hash,
+        scopes: issue_api_key.scopes.into_iter().map(Some).collect(),
+        expires_at: issue_api_key.expires_at,
+        last_used_at: None,
+        created_at: Utc::now(),
+        updated_at: None,
+        revoked_at: None,
+        created_by: principal.audit_name(),
+        updated_by: None,
+        revoked_by: None,
+    };
+
+    let result = diesel::insert_into(api_keys)
+        .values(&new_api_key)
+        .returning(ApiKey::as_returning())
+        .get_result(&mut get_connection(&pool)?);
+
+    match result {
+        Ok(api_key) => Ok(IssuedApiKey {
+            api_key,
+            key: generated.key,
+        }),
+        Err(err) => {
+            error!("Unable to issue API key, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+/// Replaces the key while keeping its owner, scopes and expiry, the previous key stops working.
+pub fn rotate_api_key(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    api_key_id: Uuid,
+    principal: &Principal,
+) -> Result<IssuedApiKey, AppError> {
+    info!("Rotate API key with id: {}", api_key_id);
+
+    let generated = generate_key();
+    let result = diesel::update(api_keys)
+        .filter(id.eq(api_key_id))
+        .filter(revoked_at.is_null())
+        .set((
+            key_prefix.eq(&generated.prefix),
+            key_hash.eq(&generated.hash),
+            updated_at.eq(Utc::now()),
+            updated_by.eq(principal.audit_name()),
+        ))
+        .returning(ApiKey::as_returning())
+        .get_result(&mut get_connection(&pool)?);
+
+    match result {
+        Ok(api_key) => Ok(IssuedApiKey {
+            api_key,
+            key: generated.key,
+        }),
+        Err(err) => {
+            error!("Unable to rotate API key, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+pub fn revoke_api_key(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    api_key_id: Uuid,
+    principal: &Principal,
+) -> Result<usize, AppError> {
+    info!("Revoke API key with id: {}", api_key_id);
+
+    let revoke_count = diesel::update(api_keys)
+        .filter(id.eq(api_key_id))
+        .filter(revoked_at.is_null())
+        .set((
+            revoked_at.eq(Utc::now()),
+            revoked_by.eq(principal.audit_name()),
+        ))
+        .execute(&mut get_connection(&pool)?);
+
+    match revoke_count {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            error!("Unable to revoke API key, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+/// Resolves the caller of a key that is neither revoked nor expired, recording when it was used.
+pub fn authenticate(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    key: &str,
+) -> Result<Principal, AppError> {
+    let now: DateTime<Utc> = Utc::now();
+
+    let result = diesel::update(api_keys)
+        .filter(key_hash.eq(hash_key(key)))
+        .filter(revoked_at.is_null())
+        .filter(expires_at.is_null().or(expires_at.gt(now)))
+        .set(last_used_at.eq(now))
+        .returning(ApiKey::as_returning())
+        .get_result(&mut get_connection(&pool)?)
+        .optional();
+
+    match result {
+        Ok(Some(api_key)) => {
+            info!(
+                "Authenticated API key {} of '{}'",
+                api_key.key_prefix, api_key.owner
+            );
+            Ok(Principal {
+                roles: api_key
+                    .scopes
+                    .iter()
+                    .flatten()
+                    .filter_map(|scope| scope.parse::<Role>().ok())
+                    .collect(),
+                subject: api_key.owner,
+            })
+        }
+        Ok(None) => {
+            warn!("Rejected unknown, expired or revoked API key");
+            Err(AppError::Unauthorized(String::from(
+                "Invalid, expired or revoked API key",
+            )))
+        }
+        Err(err) => {
+            error!("Unable to authenticate API key, error: {}", err);
+            Err(AppError::from(err))
+        }
+    }
+}
+
+fn ensure_scopes_exist(requested_scopes: &[String]) -> Result<(), AppError> {
+    match requested_scopes
+        .iter()
+        .find(|scope| scope.parse::<Role>().is_err())
+    {
+        Some(unknown) => Err(AppError::validation(
+            "scopes",
+            format!("'{}' is not a role", unknown),
+        )),
+        None => Ok(()),
+    }
+}
+
+fn get_connection(
+    pool: &Pool<ConnectionManager<PgConnection>>,
+) -> Result<PooledConnection<ConnectionManager<PgConnection>>, AppError> {
+    let connection = checkout(pool, "api_key").map_err(|err| {
+        error!("Unable to connect to database, error: {}", err);
+        AppError::from(err)
+    })?;
+
+    Ok(connection)
+}
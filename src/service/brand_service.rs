@@ -1,5 +1,8 @@
 use chrono::{DateTime, Utc};
 
+use crate::auth::principal::Principal;
+use crate::database::database::checkout;
+use crate::error::app_error::AppError;
 use crate::models::brand_models::{Brand, CreateBrand, UpdateBrand};
 use crate::schema::brands::{self, dsl::*, BoxedQuery};
 use crate::utils::brand_columns::{get_column, BrandColumn};
@@ -7,14 +10,18 @@ use crate::utils::brand_columns::{get_column, BrandColumn};
 use diesel::pg::Pg;
 use diesel::query_builder::QueryFragment;
 use diesel::r2d2::{ConnectionManager, Pool, PooledConnection};
+use diesel::sql_types;
 use diesel::{
     AppearsOnTable, ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl, SelectableHelper,
 };
 use log::{error, info, warn};
-use std::error::Error;
 use std::sync::Arc;
+use tracing::field::Empty;
+use tracing::{instrument, Span};
 use uuid::Uuid;
 
+#[instrument(skip(pool), fields(rows = Empty))]
+#[allow(clippy::too_many_arguments)]
 pub fn get_brands(
     pool: Arc<Pool<ConnectionManager<PgConnection>>>,
     offset: u32,
@@ -23,10 +30,11 @@ pub fn get_brands(
     sort_order: String,
     filter_by: String,
     filter_term: String,
-) -> Result<Vec<Brand>, Box<dyn Error>> {
+    include_deleted: bool,
+) -> Result<Vec<Brand>, AppError> {
     info!(
-        "Get all brands starting at '{}', limited to '{}', sort by '{}' order '{}', filter by '{}' term '{}'",
-        offset, limit, sort_by, sort_order, filter_by, filter_term
+        "Get all brands starting at '{}', limited to '{}', sort by '{}' order '{}', filter by '{}' term '{}', include deleted '{}'",
+        offset, limit, sort_by, sort_order, filter_by, filter_term, include_deleted
     );
 
     let mut query = brands::table
@@ -34,6 +42,10 @@ pub fn get_brands(
         .limit(limit as i64)
         .offset(offset as i64);
 
+    if !include_deleted {
+        query = query.filter(deleted_at.is_null());
+    }
+
     let sort_column: BrandColumn = get_column(sort_by.as_str());
     query = match sort_column {
         BrandColumn::Text(column) => sort_by_column(query, column, Some(sort_order)),
@@ -51,7 +63,10 @@ pub fn get_brands(
     let brand_list = query.load(&mut get_connection(&pool)?);
 
     match brand_list {
-        Ok(brand_list) => Ok(brand_list),
+        Ok(brand_list) => {
+            Span::current().record("rows", brand_list.len());
+            Ok(brand_list)
+        }
         Err(err) => {
             error!("Unable to retrieve brands, error: {}", err);
             Err(err.into())
@@ -59,19 +74,33 @@ pub fn get_brands(
     }
 }
 
+#[instrument(skip(pool), fields(rows = Empty))]
 pub fn get_brand(
     pool: Arc<Pool<ConnectionManager<PgConnection>>>,
     brand_id: Uuid,
-) -> Result<Brand, Box<dyn Error>> {
-    info!("Get brand with id: {}", brand_id);
+    include_deleted: bool,
+) -> Result<Brand, AppError> {
+    info!(
+        "Get brand with id: {}, include deleted '{}'",
+        brand_id, include_deleted
+    );
 
-    let result = brands::table
+    let mut query = brands::table
         .select(Brand::as_select())
         .filter(id.eq(brand_id))
-        .first(&mut get_connection(&pool)?);
+        .into_boxed();
+
+    if !include_deleted {
+        query = query.filter(deleted_at.is_null());
+    }
+
+    let result = query.first(&mut get_connection(&pool)?);
 
     match result {
-        Ok(result) => Ok(result),
+        Ok(result) => {
+            Span::current().record("rows", 1);
+            Ok(result)
+        }
         Err(err) => {
             error!("Unable to retrieve brand, error: {}", err);
             Err(err.into())
@@ -79,10 +108,12 @@ pub fn get_brand(
     }
 }
 
+#[instrument(skip(pool, principal), fields(caller = %principal.subject, rows = Empty))]
 pub fn create_brand(
     pool: Arc<Pool<ConnectionManager<PgConnection>>>,
     create_brand: CreateBrand,
-) -> Result<Brand, Box<dyn Error>> {
+    principal: &Principal,
+) -> Result<Brand, AppError> {
     info!("Create brand: {:?}", create_brand);
 
     let new_brand: Brand = Brand {
@@ -93,7 +124,7 @@ pub fn create_brand(
         created_at: Utc::now(),
         updated_at: None,
         deleted_at: None,
-        created_by: String::from("admin"), // TODO get it from request.
+        created_by: principal.audit_name(),
         updated_by: None,
         deleted_by: None,
     };
@@ -104,7 +135,10 @@ pub fn create_brand(
         .get_result(&mut get_connection(&pool)?);
 
     match create_brand {
-        Ok(brand) => Ok(brand),
+        Ok(brand) => {
+            Span::current().record("rows", 1);
+            Ok(brand)
+        }
         Err(err) => {
             error!("Unable to create brand, error: {}", err);
             Err(err.into())
@@ -112,20 +146,25 @@ pub fn create_brand(
     }
 }
 
+#[instrument(skip(pool, principal), fields(caller = %principal.subject, rows = Empty))]
 pub fn create_brands(
     pool: Arc<Pool<ConnectionManager<PgConnection>>>,
     new_brands: Vec<CreateBrand>,
-) -> Result<Vec<Brand>, Box<dyn Error>> {
+    principal: &Principal,
+) -> Result<Vec<Brand>, AppError> {
     info!("Create brands: {:?}", new_brands);
 
     if new_brands.is_empty() {
         warn!("No brands to create");
-        return Err("No brands to create".into());
+        return Err(AppError::validation(
+            "body",
+            "must contain at least one brand",
+        ));
     }
 
     let mut brand_entities: Vec<Brand> = Vec::new();
     let now: DateTime<Utc> = Utc::now();
-    let default_created_by = String::from("admin");
+    let default_created_by = principal.audit_name();
 
     for new_brand in new_brands {
         brand_entities.push(Brand {
@@ -148,7 +187,10 @@ pub fn create_brands(
         .get_results(&mut get_connection(&pool)?);
 
     match result {
-        Ok(created) => Ok(created),
+        Ok(created) => {
+            Span::current().record("rows", created.len());
+            Ok(created)
+        }
         Err(err) => {
             error!("Unable to create brands, error: {}", err);
             Err(err.into())
@@ -156,24 +198,30 @@ pub fn create_brands(
     }
 }
 
+#[instrument(skip(pool, principal), fields(caller = %principal.subject, rows = Empty))]
 pub fn update_brand(
     pool: Arc<Pool<ConnectionManager<PgConnection>>>,
     brand_id: Uuid,
     updated_brand: UpdateBrand,
-) -> Result<usize, Box<dyn Error>> {
+    principal: &Principal,
+) -> Result<usize, AppError> {
     info!("Update brand {} to {:?}", brand_id, updated_brand);
 
     let update_count = diesel::update(brands)
         .filter(id.eq(brand_id))
+        .filter(deleted_at.is_null())
         .set((
             updated_brand,
             updated_at.eq(Utc::now()),
-            updated_by.eq(String::from("admin")),
+            updated_by.eq(principal.audit_name()),
         ))
         .execute(&mut get_connection(&pool)?);
 
     match update_count {
-        Ok(count) => Ok(count),
+        Ok(count) => {
+            Span::current().record("rows", count);
+            Ok(count)
+        }
         Err(err) => {
             error!("Unable to update brands, error: {}", err);
             Err(err.into())
@@ -181,18 +229,28 @@ pub fn update_brand(
     }
 }
 
+#[instrument(skip(pool, principal), fields(caller = %principal.subject, rows = Empty))]
 pub fn delete_brand(
     pool: Arc<Pool<ConnectionManager<PgConnection>>>,
     brand_id: Uuid,
-) -> Result<usize, Box<dyn Error>> {
+    principal: &Principal,
+) -> Result<usize, AppError> {
     info!("Delete brand with id: {}", brand_id);
 
-    let delete_count = diesel::delete(brands)
+    let delete_count = diesel::update(brands)
         .filter(id.eq(brand_id))
+        .filter(deleted_at.is_null())
+        .set((
+            deleted_at.eq(Utc::now()),
+            deleted_by.eq(principal.audit_name()),
+        ))
         .execute(&mut get_connection(&pool)?);
 
     match delete_count {
-        Ok(count) => Ok(count),
+        Ok(count) => {
+            Span::current().record("rows", count);
+            Ok(count)
+        }
         Err(err) => {
             error!("Unable to delete brand, error: {}", err);
             Err(err.into())
@@ -200,19 +258,27 @@ pub fn delete_brand(
     }
 }
 
+#[instrument(skip(pool, principal), fields(caller = %principal.subject, rows = Empty))]
 pub fn delete_brands(
     pool: Arc<Pool<ConnectionManager<PgConnection>>>,
     brands_ids: Vec<Uuid>,
-) -> Result<usize, Box<dyn Error>> {
+    principal: &Principal,
+) -> Result<usize, AppError> {
     info!("Delete brands with ids: {:?}", brands_ids);
 
-    let delete_count = diesel::delete(brands)
+    let delete_count = diesel::update(brands)
         .filter(id.eq_any(brands_ids))
+        .filter(deleted_at.is_null())
+        .set((
+            deleted_at.eq(Utc::now()),
+            deleted_by.eq(principal.audit_name()),
+        ))
         .execute(&mut get_connection(&pool)?);
 
     match delete_count {
         Ok(count) => {
-            info!("Posts delete count: {}", count);
+            Span::current().record("rows", count);
+            info!("Brands delete count: {}", count);
             Ok(count)
         }
         Err(err) => {
@@ -222,18 +288,83 @@ pub fn delete_brands(
     }
 }
 
+#[instrument(skip(pool, principal), fields(caller = %principal.subject, rows = Empty))]
+pub fn restore_brand(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    brand_id: Uuid,
+    principal: &Principal,
+) -> Result<usize, AppError> {
+    info!("Restore brand with id: {}", brand_id);
+
+    let restore_count = diesel::update(brands)
+        .filter(id.eq(brand_id))
+        .filter(deleted_at.is_not_null())
+        .set((
+            deleted_at.eq(None::<DateTime<Utc>>),
+            deleted_by.eq(None::<String>),
+            updated_at.eq(Utc::now()),
+            updated_by.eq(principal.audit_name()),
+        ))
+        .execute(&mut get_connection(&pool)?);
+
+    match restore_count {
+        Ok(count) => {
+            Span::current().record("rows", count);
+            Ok(count)
+        }
+        Err(err) => {
+            error!("Unable to restore brand, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+/// Permanently removes a brand, which must have been soft deleted first.
+#[instrument(skip(pool), fields(rows = Empty))]
+pub fn purge_brand(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    brand_id: Uuid,
+) -> Result<usize, AppError> {
+    info!("Purge brand with id: {}", brand_id);
+
+    let purge_count = diesel::delete(brands)
+        .filter(id.eq(brand_id))
+        .filter(deleted_at.is_not_null())
+        .execute(&mut get_connection(&pool)?);
+
+    match purge_count {
+        Ok(count) => {
+            Span::current().record("rows", count);
+            Ok(count)
+        }
+        Err(err) => {
+            error!("Unable to purge brand, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+/// Ids of the brands that are not soft deleted, to hide the catalog entries of deleted ones.
+pub fn live_brand_ids() -> BoxedQuery<'static, Pg, sql_types::Uuid> {
+    brands::table
+        .select(id)
+        .filter(deleted_at.is_null())
+        .into_boxed()
+}
+
 fn get_connection(
     pool: &Pool<ConnectionManager<PgConnection>>,
-) -> Result<PooledConnection<ConnectionManager<PgConnection>>, Box<dyn Error>> {
-    let connection = pool.get().map_err(|err| {
+) -> Result<PooledConnection<ConnectionManager<PgConnection>>, AppError> {
+    let connection = checkout(pool, "brand").map_err(|err| {
         error!("Unable to connect to database, error: {}", err);
-        Box::new(err) as Box<dyn Error>
+        AppError::from(err)
     })?;
 
     Ok(connection)
 }
 
 // https://stackoverflow.com/a/62029781
+#[allow(clippy::multiple_bound_locations, clippy::option_as_ref_deref)]
 fn sort_by_column<U: 'static + std::marker::Send>(
     query: BoxedQuery<'static, Pg>,
     column: U,
@@ -0,0 +1,471 @@
+use chrono::{DateTime, Utc};
+
+use crate::auth::principal::Principal;
+use crate::auth::role::Role;
+use crate::database::database::checkout;
+use crate::error::app_error::AppError;
+use crate::models::brand_models::Brand;
+use crate::models::car_models::{Car, CarDetails, CreateCar, UpdateCar};
+use crate::models::model_models::Model;
+use crate::models::version_models::Version;
+use crate::schema::cars::{self, dsl::*};
+use crate::schema::{brands, models, versions};
+use crate::service::version_service::live_version_ids;
+use crate::utils::car_columns::{get_column, CarColumn, CarQuerySource};
+
+use diesel::dsl::{count_star, exists, AsSelect, InnerJoin, IntoBoxed, Select};
+use diesel::pg::Pg;
+use diesel::query_builder::QueryFragment;
+use diesel::r2d2::{ConnectionManager, Pool, PooledConnection};
+use diesel::sql_types;
+use diesel::{
+    AppearsOnTable, ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl, SelectableHelper,
+};
+use log::{error, info, warn};
+use std::sync::Arc;
+use uuid::Uuid;
+
+type CarDetailsQuery = IntoBoxed<
+    'static,
+    Select<
+        InnerJoin<cars::table, InnerJoin<versions::table, InnerJoin<models::table, brands::table>>>,
+        (
+            AsSelect<Car, Pg>,
+            AsSelect<Version, Pg>,
+            AsSelect<Model, Pg>,
+            AsSelect<Brand, Pg>,
+        ),
+    >,
+    Pg,
+>;
+
+#[allow(clippy::too_many_arguments)]
+pub fn get_cars(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    offset: u32,
+    limit: u32,
+    sort_by: String,
+    sort_order: String,
+    filter_by: String,
+    filter_term: String,
+    include_deleted: bool,
+) -> Result<Vec<CarDetails>, AppError> {
+    info!(
+        "Get all cars starting at '{}', limited to '{}', sort by '{}' order '{}', filter by '{}' term '{}', include deleted '{}'",
+        offset, limit, sort_by, sort_order, filter_by, filter_term, include_deleted
+    );
+
+    let mut query = car_details_query()
+        .limit(limit as i64)
+        .offset(offset as i64);
+
+    if !include_deleted {
+        query = without_deleted(query);
+    }
+
+    let sort_column: CarColumn = get_column(sort_by.as_str());
+    query = match sort_column {
+        CarColumn::Integer(column) => sort_by_column(query, column, Some(sort_order)),
+        CarColumn::Text(column) => sort_by_column(query, column, Some(sort_order)),
+        CarColumn::NullableText(column) => sort_by_column(query, column, Some(sort_order)),
+        CarColumn::Bool(column) => sort_by_column(query, column, Some(sort_order)),
+        CarColumn::Uuid(column) => sort_by_column(query, column, Some(sort_order)),
+    };
+
+    if !filter_by.is_empty() && !filter_term.is_empty() {
+        let filter_column: CarColumn = get_column(filter_by.as_str());
+        query = match filter_column {
+            CarColumn::Integer(column) => query.filter(column.eq(filter_term.parse::<i32>()?)),
+            CarColumn::Text(column) => query.filter(column.eq(filter_term)),
+            CarColumn::NullableText(column) => query.filter(column.eq(filter_term)),
+            CarColumn::Bool(column) => query.filter(column.eq(filter_term.parse::<bool>()?)),
+            CarColumn::Uuid(column) => query.filter(column.eq(filter_term.parse::<Uuid>()?)),
+        };
+    }
+
+    let car_list = query.load::<(Car, Version, Model, Brand)>(&mut get_connection(&pool)?);
+
+    match car_list {
+        Ok(car_list) => Ok(car_list.into_iter().map(CarDetails::from).collect()),
+        Err(err) => {
+            error!("Unable to retrieve cars, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+pub fn get_car(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    car_id: Uuid,
+    include_deleted: bool,
+) -> Result<CarDetails, AppError> {
+    info!(
+        "Get car with id: {}, include deleted '{}'",
+        car_id, include_deleted
+    );
+
+    let mut query = car_details_query().filter(cars::id.eq(car_id));
+
+    if !include_deleted {
+        query = without_deleted(query);
+    }
+
+    let result = query.first::<(Car, Version, Model, Brand)>(&mut get_connection(&pool)?);
+
+    match result {
+        Ok(result) => Ok(CarDetails::from(result)),
+        Err(err) => {
+            error!("Unable to retrieve car, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+pub fn create_car(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    create_car: CreateCar,
+    principal: &Principal,
+) -> Result<CarDetails, AppError> {
+    info!("Create car: {:?}", create_car);
+
+    let mut connection = get_connection(&pool)?;
+    ensure_versions_exist(&mut connection, vec![create_car.version_id])?;
+
+    let new_car: Car = Car {
+        id: Uuid::new_v4(),
+        version_id: create_car.version_id,
+        mileage: create_car.mileage,
+        color: create_car.color,
+        armored: create_car.armored,
+        owner: create_car.owner,
+        created_at: Utc::now(),
+        updated_at: None,
+        deleted_at: None,
+        created_by: principal.audit_name(),
+        updated_by: None,
+        deleted_by: None,
+    };
+
+    let created_car = diesel::insert_into(cars)
+        .values(&new_car)
+        .execute(&mut connection)
+        .and_then(|_| {
+            car_details_query()
+                .filter(cars::id.eq(new_car.id))
+                .first::<(Car, Version, Model, Brand)>(&mut connection)
+        });
+
+    match created_car {
+        Ok(car) => Ok(CarDetails::from(car)),
+        Err(err) => {
+            error!("Unable to create car, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+pub fn create_cars(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    new_cars: Vec<CreateCar>,
+    principal: &Principal,
+) -> Result<Vec<CarDetails>, AppError> {
+    info!("Create cars: {:?}", new_cars);
+
+    if new_cars.is_empty() {
+        warn!("No cars to create");
+        return Err(AppError::validation(
+            "body",
+            "must contain at least one car",
+        ));
+    }
+
+    let mut connection = get_connection(&pool)?;
+    ensure_versions_exist(
+        &mut connection,
+        new_cars.iter().map(|new_car| new_car.version_id).collect(),
+    )?;
+
+    let mut car_entities: Vec<Car> = Vec::new();
+    let now: DateTime<Utc> = Utc::now();
+    let default_created_by = principal.audit_name();
+
+    for new_car in new_cars {
+        car_entities.push(Car {
+            id: Uuid::new_v4(),
+            version_id: new_car.version_id,
+            mileage: new_car.mileage,
+            color: new_car.color,
+            armored: new_car.armored,
+            owner: new_car.owner,
+            created_at: now,
+            updated_at: None,
+            deleted_at: None,
+            created_by: default_created_by.clone(),
+            updated_by: None,
+            deleted_by: None,
+        });
+    }
+
+    let car_ids: Vec<Uuid> = car_entities.iter().map(|car| car.id).collect();
+    let result = diesel::insert_into(cars)
+        .values(&car_entities)
+        .execute(&mut connection)
+        .and_then(|_| {
+            car_details_query()
+                .filter(cars::id.eq_any(car_ids))
+                .load::<(Car, Version, Model, Brand)>(&mut connection)
+        });
+
+    match result {
+        Ok(created) => Ok(created.into_iter().map(CarDetails::from).collect()),
+        Err(err) => {
+            error!("Unable to create cars, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+pub fn update_car(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    car_id: Uuid,
+    updated_car: UpdateCar,
+    principal: &Principal,
+) -> Result<usize, AppError> {
+    info!("Update car {} to {:?}", car_id, updated_car);
+
+    let mut connection = get_connection(&pool)?;
+    if let Some(new_version_id) = updated_car.version_id {
+        ensure_versions_exist(&mut connection, vec![new_version_id])?;
+    }
+
+    let mut query = diesel::update(cars)
+        .filter(id.eq(car_id))
+        .filter(deleted_at.is_null())
+        .set((
+            updated_car,
+            updated_at.eq(Utc::now()),
+            updated_by.eq(principal.audit_name()),
+        ))
+        .into_boxed();
+    if !principal.has_role(Role::Admin) {
+        query = query.filter(created_by.eq(principal.subject.clone()));
+    }
+    let update_count = query.execute(&mut connection);
+
+    match update_count {
+        Ok(0) => unmodified(&mut connection, car_id, principal),
+        Ok(count) => Ok(count),
+        Err(err) => {
+            error!("Unable to update cars, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+pub fn delete_car(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    car_id: Uuid,
+    principal: &Principal,
+) -> Result<usize, AppError> {
+    info!("Delete car with id: {}", car_id);
+
+    let mut connection = get_connection(&pool)?;
+    let mut query = diesel::update(cars)
+        .filter(id.eq(car_id))
+        .filter(deleted_at.is_null())
+        .set((
+            deleted_at.eq(Utc::now()),
+            deleted_by.eq(principal.audit_name()),
+        ))
+        .into_boxed();
+    if !principal.has_role(Role::Admin) {
+        query = query.filter(created_by.eq(principal.subject.clone()));
+    }
+    let delete_count = query.execute(&mut connection);
+
+    match delete_count {
+        Ok(0) => unmodified(&mut connection, car_id, principal),
+        Ok(count) => Ok(count),
+        Err(err) => {
+            error!("Unable to delete car, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+pub fn delete_cars(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    cars_ids: Vec<Uuid>,
+    principal: &Principal,
+) -> Result<usize, AppError> {
+    info!("Delete cars with ids: {:?}", cars_ids);
+
+    let delete_count = diesel::update(cars)
+        .filter(id.eq_any(cars_ids))
+        .filter(deleted_at.is_null())
+        .set((
+            deleted_at.eq(Utc::now()),
+            deleted_by.eq(principal.audit_name()),
+        ))
+        .execute(&mut get_connection(&pool)?);
+
+    match delete_count {
+        Ok(count) => {
+            info!("Cars delete count: {}", count);
+            Ok(count)
+        }
+        Err(err) => {
+            error!("Unable to delete cars, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+pub fn restore_car(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    car_id: Uuid,
+    principal: &Principal,
+) -> Result<usize, AppError> {
+    info!("Restore car with id: {}", car_id);
+
+    let restore_count = diesel::update(cars)
+        .filter(id.eq(car_id))
+        .filter(deleted_at.is_not_null())
+        .set((
+            deleted_at.eq(None::<DateTime<Utc>>),
+            deleted_by.eq(None::<String>),
+            updated_at.eq(Utc::now()),
+            updated_by.eq(principal.audit_name()),
+        ))
+        .execute(&mut get_connection(&pool)?);
+
+    match restore_count {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            error!("Unable to restore car, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+/// Permanently removes a car, which must have been soft deleted first.
+pub fn purge_car(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    car_id: Uuid,
+) -> Result<usize, AppError> {
+    info!("Purge car with id: {}", car_id);
+
+    let purge_count = diesel::delete(cars)
+        .filter(id.eq(car_id))
+        .filter(deleted_at.is_not_null())
+        .execute(&mut get_connection(&pool)?);
+
+    match purge_count {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            error!("Unable to purge car, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+/// Ids of the cars that are not soft deleted, nor is their version, model or brand.
+pub fn live_car_ids() -> cars::BoxedQuery<'static, Pg, sql_types::Uuid> {
+    cars::table
+        .select(id)
+        .filter(deleted_at.is_null())
+        .filter(version_id.eq_any(live_version_ids()))
+        .into_boxed()
+}
+
+fn car_details_query() -> CarDetailsQuery {
+    cars::table
+        .inner_join(versions::table.inner_join(models::table.inner_join(brands::table)))
+        .select((
+            Car::as_select(),
+            Version::as_select(),
+            Model::as_select(),
+            Brand::as_select(),
+        ))
+        .into_boxed()
+}
+
+fn without_deleted(query: CarDetailsQuery) -> CarDetailsQuery {
+    query
+        .filter(cars::deleted_at.is_null())
+        .filter(versions::deleted_at.is_null())
+        .filter(models::deleted_at.is_null())
+        .filter(brands::deleted_at.is_null())
+}
+
+/// Sellers modify only the cars they created, so a car left untouched is either missing (0 rows,
+/// a 404) or someone else's (403).
+fn unmodified(
+    connection: &mut PgConnection,
+    car_id: Uuid,
+    principal: &Principal,
+) -> Result<usize, AppError> {
+    let found: bool = diesel::select(exists(
+        cars.filter(id.eq(car_id)).filter(deleted_at.is_null()),
+    ))
+    .get_result(connection)?;
+
+    if found {
+        warn!(
+            "'{}' tried to modify car {} of another seller",
+            principal.subject, car_id
+        );
+        return Err(AppError::Forbidden(String::from(
+            "Only the seller who created a car, or an admin, may modify it",
+        )));
+    }
+
+    Ok(0)
+}
+
+fn ensure_versions_exist(
+    connection: &mut PgConnection,
+    mut versions_ids: Vec<Uuid>,
+) -> Result<(), AppError> {
+    versions_ids.sort();
+    versions_ids.dedup();
+
+    let found: i64 = versions::table
+        .filter(versions::id.eq_any(&versions_ids))
+        .filter(versions::id.eq_any(live_version_ids()))
+        .select(count_star())
+        .get_result(connection)?;
+
+    if found as usize != versions_ids.len() {
+        warn!("Unknown version in: {:?}", versions_ids);
+        return Err(AppError::validation(
+            "version_id",
+            format!("unknown version in: {:?}", versions_ids),
+        ));
+    }
+
+    Ok(())
+}
+
+fn get_connection(
+    pool: &Pool<ConnectionManager<PgConnection>>,
+) -> Result<PooledConnection<ConnectionManager<PgConnection>>, AppError> {
+    let connection = checkout(pool, "car").map_err(|err| {
+        error!("Unable to connect to database, error: {}", err);
+        AppError::from(err)
+    })?;
+
+    Ok(connection)
+}
+
+// https://stackoverflow.com/a/62029781
+fn sort_by_column<U>(query: CarDetailsQuery, column: U, sort_dir: Option<String>) -> CarDetailsQuery
+where
+    U: 'static + Send + ExpressionMethods + QueryFragment<Pg> + AppearsOnTable<CarQuerySource>,
+{
+    match sort_dir.as_deref() {
+        Some("asc") => query.order_by(column.asc()),
+        Some("desc") => query.order_by(column.desc()),
+        _ => query,
+    }
+}
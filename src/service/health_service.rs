@@ -0,0 +1,84 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::{PgConnection, RunQueryDsl};
+use log::{error, info, warn};
+
+use crate::database::database::{checkout, pool_status};
+use crate::database::migration::pending_migrations;
+use crate::models::health_models::{ComponentHealth, Health};
+
+/// Checks a connection out of the pool, runs `SELECT 1` on it and looks for pending migrations.
+pub fn check_readiness(pool: Arc<Pool<ConnectionManager<PgConnection>>>) -> Health {
+    info!("Check readiness");
+
+    let mut components: BTreeMap<String, ComponentHealth> = BTreeMap::new();
+
+    let started = Instant::now();
+    let connection = checkout(&pool, "health");
+    let status = pool_status(&pool);
+    let pool_detail = format!(
+        "{} idle, {} in use, {} waiting, max {}",
+        status.idle, status.in_use, status.waiting, status.max_size
+    );
+    let mut connection = match connection {
+        Ok(connection) => {
+            components.insert(
+                String::from("pool"),
+                ComponentHealth::up(started.elapsed(), Some(pool_detail)),
+            );
+            connection
+        }
+        Err(err) => {
+            error!("Unable to connect to database, error: {}", err);
+            let skipped = String::from("Not checked, no database connection");
+            components.insert(
+                String::from("pool"),
+                ComponentHealth::down(started.elapsed(), pool_detail),
+            );
+            components.insert(
+                String::from("database"),
+                ComponentHealth::down(Duration::ZERO, skipped.clone()),
+            );
+            components.insert(
+                String::from("migrations"),
+                ComponentHealth::down(Duration::ZERO, skipped),
+            );
+            return Health::from_components(components);
+        }
+    };
+
+    let started = Instant::now();
+    let database = match diesel::sql_query("SELECT 1").execute(&mut connection) {
+        Ok(_) => ComponentHealth::up(started.elapsed(), None),
+        Err(err) => {
+            error!("Unable to query database, error: {}", err);
+            ComponentHealth::down(started.elapsed(), String::from("Database query failed"))
+        }
+    };
+    components.insert(String::from("database"), database);
+
+    let started = Instant::now();
+    let migrations = match pending_migrations(&mut connection) {
+        Ok(pending) if pending.is_empty() => ComponentHealth::up(started.elapsed(), None),
+        Ok(pending) => {
+            warn!("Pending migrations: {:?}", pending);
+            ComponentHealth::down(
+                started.elapsed(),
+                format!("Pending migrations: {}", pending.join(", ")),
+            )
+        }
+        Err(err) => {
+            error!("Unable to check pending migrations, error: {}", err);
+            ComponentHealth::down(
+                started.elapsed(),
+                String::from("Unable to check pending migrations"),
+            )
+        }
+    };
+    components.insert(String::from("migrations"), migrations);
+
+    Health::from_components(components)
+}
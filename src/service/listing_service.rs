@@ -0,0 +1,347 @@
+use chrono::{DateTime, Utc};
+
+use crate::auth::principal::Principal;
+use crate::auth::role::Role;
+use crate::database::database::checkout;
+use crate::error::app_error::AppError;
+use crate::models::brand_models::Brand;
+use crate::models::car_models::Car;
+use crate::models::listing_models::{CreateListing, Listing, ListingDocument};
+use crate::models::model_models::Model;
+use crate::models::seller_models::Seller;
+use crate::models::version_models::Version;
+use crate::schema::listings::{self, dsl::*};
+use crate::schema::{brands, cars, models, sellers, versions};
+use crate::service::car_service::live_car_ids;
+use crate::utils::listing_columns::{get_column, ListingColumn, ListingQuerySource};
+
+use diesel::dsl::{AsSelect, InnerJoin, IntoBoxed, Select};
+use diesel::pg::Pg;
+use diesel::query_builder::QueryFragment;
+use diesel::r2d2::{ConnectionManager, Pool, PooledConnection};
+use diesel::{
+    AppearsOnTable, ExpressionMethods, OptionalExtension, PgConnection, QueryDsl, RunQueryDsl,
+    SelectableHelper,
+};
+use log::{error, info, warn};
+use std::sync::Arc;
+use uuid::Uuid;
+
+type ListingRow = (Listing, Car, Version, Model, Brand, Seller);
+
+type ListingDocumentQuery = IntoBoxed<
+    'static,
+    Select<
+        InnerJoin<
+            InnerJoin<
+                listings::table,
+                InnerJoin<
+                    cars::table,
+                    InnerJoin<versions::table, InnerJoin<models::table, brands::table>>,
+                >,
+            >,
+            sellers::table,
+        >,
+        (
+            AsSelect<Listing, Pg>,
+            AsSelect<Car, Pg>,
+            AsSelect<Version, Pg>,
+            AsSelect<Model, Pg>,
+            AsSelect<Brand, Pg>,
+            AsSelect<Seller, Pg>,
+        ),
+    >,
+    Pg,
+>;
+
+#[allow(clippy::too_many_arguments)]
+pub fn get_listings(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    offset: u32,
+    limit: u32,
+    sort_by: String,
+    sort_order: String,
+    filter_by: String,
+    filter_term: String,
+    include_deleted: bool,
+) -> Result<Vec<ListingDocument>, AppError> {
+    info!(
+        "Get all listings starting at '{}', limited to '{}', sort by '{}' order '{}', filter by '{}' term '{}', include deleted '{}'",
+        offset, limit, sort_by, sort_order, filter_by, filter_term, include_deleted
+    );
+
+    let mut query = listing_document_query()
+        .limit(limit as i64)
+        .offset(offset as i64);
+
+    if !include_deleted {
+        query = without_deleted(query);
+    }
+
+    let sort_column: ListingColumn = get_column(sort_by.as_str());
+    query = match sort_column {
+        ListingColumn::Integer(column) => sort_by_column(query, column, Some(sort_order)),
+        ListingColumn::Text(column) => sort_by_column(query, column, Some(sort_order)),
+        ListingColumn::Bool(column) => sort_by_column(query, column, Some(sort_order)),
+        ListingColumn::Uuid(column) => sort_by_column(query, column, Some(sort_order)),
+    };
+
+    if !filter_by.is_empty() && !filter_term.is_empty() {
+        let filter_column: ListingColumn = get_column(filter_by.as_str());
+        query = match filter_column {
+            ListingColumn::Integer(column) => query.filter(column.eq(filter_term.parse::<i32>()?)),
+            ListingColumn::Text(column) => query.filter(column.eq(filter_term)),
+            ListingColumn::Bool(column) => query.filter(column.eq(filter_term.parse::<bool>()?)),
+            ListingColumn::Uuid(column) => query.filter(column.eq(filter_term.parse::<Uuid>()?)),
+        };
+    }
+
+    let listing_list = query.load::<ListingRow>(&mut get_connection(&pool)?);
+
+    match listing_list {
+        Ok(listing_list) => Ok(listing_list
+            .into_iter()
+            .map(ListingDocument::from)
+            .collect()),
+        Err(err) => {
+            error!("Unable to retrieve listings, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+pub fn get_listing(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    listing_id: Uuid,
+    include_deleted: bool,
+) -> Result<ListingDocument, AppError> {
+    info!(
+        "Get listing with id: {}, include deleted '{}'",
+        listing_id, include_deleted
+    );
+
+    let mut query = listing_document_query().filter(listings::id.eq(listing_id));
+
+    if !include_deleted {
+        query = without_deleted(query);
+    }
+
+    let result = query.first::<ListingRow>(&mut get_connection(&pool)?);
+
+    match result {
+        Ok(result) => Ok(ListingDocument::from(result)),
+        Err(err) => {
+            error!("Unable to retrieve listing, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+pub fn create_listing(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    create_listing: CreateListing,
+    principal: &Principal,
+) -> Result<ListingDocument, AppError> {
+    info!("Create listing: {:?}", create_listing);
+
+    let mut connection = get_connection(&pool)?;
+
+    let car_creator: String = cars::table
+        .select(cars::created_by)
+        .filter(cars::id.eq(create_listing.car_id))
+        .filter(cars::id.eq_any(live_car_ids()))
+        .first(&mut connection)
+        .optional()?
+        .ok_or_else(|| {
+            warn!("Unknown car: {}", create_listing.car_id);
+            AppError::validation("car_id", format!("unknown car: {}", create_listing.car_id))
+        })?;
+
+    let seller: Seller = sellers::table
+        .select(Seller::as_select())
+        .filter(sellers::id.eq(create_listing.seller_id))
+        .filter(sellers::deleted_at.is_null())
+        .first(&mut connection)
+        .optional()?
+        .ok_or_else(|| {
+            warn!("Unknown seller: {}", create_listing.seller_id);
+            AppError::validation(
+                "seller_id",
+                format!("unknown seller: {}", create_listing.seller_id),
+            )
+        })?;
+
+    // Sellers list their own cars under the seller records they created, admins any of them
+    if !principal.has_role(Role::Admin)
+        && (car_creator != principal.subject || seller.created_by != principal.subject)
+    {
+        warn!(
+            "'{}' tried to list car {} for seller {}",
+            principal.subject, create_listing.car_id, create_listing.seller_id
+        );
+        return Err(AppError::Forbidden(String::from(
+            "Listings can only be created for your own cars and sellers",
+        )));
+    }
+
+    let new_listing: Listing = Listing {
+        id: Uuid::new_v4(),
+        car_id: create_listing.car_id,
+        seller_id: create_listing.seller_id,
+        price: create_listing.price,
+        exchange: create_listing.exchange,
+        phone: create_listing.phone.unwrap_or(seller.phone),
+        email: create_listing.email.unwrap_or(seller.email),
+        created_at: Utc::now(),
+        updated_at: None,
+        deleted_at: None,
+        created_by: principal.audit_name(),
+        updated_by: None,
+        deleted_by: None,
+    };
+
+    let created_listing = diesel::insert_into(listings)
+        .values(&new_listing)
+        .execute(&mut connection)
+        .and_then(|_| {
+            listing_document_query()
+                .filter(listings::id.eq(new_listing.id))
+                .first::<ListingRow>(&mut connection)
+        });
+
+    match created_listing {
+        Ok(listing) => Ok(ListingDocument::from(listing)),
+        Err(err) => {
+            error!("Unable to create listing, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+pub fn delete_listing(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    listing_id: Uuid,
+    principal: &Principal,
+) -> Result<usize, AppError> {
+    info!("Delete listing with id: {}", listing_id);
+
+    let delete_count = diesel::update(listings)
+        .filter(id.eq(listing_id))
+        .filter(deleted_at.is_null())
+        .set((
+            deleted_at.eq(Utc::now()),
+            deleted_by.eq(principal.audit_name()),
+        ))
+        .execute(&mut get_connection(&pool)?);
+
+    match delete_count {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            error!("Unable to delete listing, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+pub fn restore_listing(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    listing_id: Uuid,
+    principal: &Principal,
+) -> Result<usize, AppError> {
+    info!("Restore listing with id: {}", listing_id);
+
+    let restore_count = diesel::update(listings)
+        .filter(id.eq(listing_id))
+        .filter(deleted_at.is_not_null())
+        .set((
+            deleted_at.eq(None::<DateTime<Utc>>),
+            deleted_by.eq(None::<String>),
+            updated_at.eq(Utc::now()),
+            updated_by.eq(principal.audit_name()),
+        ))
+        .execute(&mut get_connection(&pool)?);
+
+    match restore_count {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            error!("Unable to restore listing, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+/// Permanently removes a listing, which must have been soft deleted first.
+pub fn purge_listing(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    listing_id: Uuid,
+) -> Result<usize, AppError> {
+    info!("Purge listing with id: {}", listing_id);
+
+    let purge_count = diesel::delete(listings)
+        .filter(id.eq(listing_id))
+        .filter(deleted_at.is_not_null())
+        .execute(&mut get_connection(&pool)?);
+
+    match purge_count {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            error!("Unable to purge listing, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+fn listing_document_query() -> ListingDocumentQuery {
+    listings::table
+        .inner_join(
+            cars::table
+                .inner_join(versions::table.inner_join(models::table.inner_join(brands::table))),
+        )
+        .inner_join(sellers::table)
+        .select((
+            Listing::as_select(),
+            Car::as_select(),
+            Version::as_select(),
+            Model::as_select(),
+            Brand::as_select(),
+            Seller::as_select(),
+        ))
+        .into_boxed()
+}
+
+fn without_deleted(query: ListingDocumentQuery) -> ListingDocumentQuery {
+    query
+        .filter(listings::deleted_at.is_null())
+        .filter(cars::deleted_at.is_null())
+        .filter(versions::deleted_at.is_null())
+        .filter(models::deleted_at.is_null())
+        .filter(brands::deleted_at.is_null())
+        .filter(sellers::deleted_at.is_null())
+}
+
+fn get_connection(
+    pool: &Pool<ConnectionManager<PgConnection>>,
+) -> Result<PooledConnection<ConnectionManager<PgConnection>>, AppError> {
+    let connection = checkout(pool, "listing").map_err(|err| {
+        error!("Unable to connect to database, error: {}", err);
+        AppError::from(err)
+    })?;
+
+    Ok(connection)
+}
+
+// https://stackoverflow.com/a/62029781
+fn sort_by_column<U>(
+    query: ListingDocumentQuery,
+    column: U,
+    sort_dir: Option<String>,
+) -> ListingDocumentQuery
+where
+    U: 'static + Send + ExpressionMethods + QueryFragment<Pg> + AppearsOnTable<ListingQuerySource>,
+{
+    match sort_dir.as_deref() {
+        Some("asc") => query.order_by(column.asc()),
+        Some("desc") => query.order_by(column.desc()),
+        _ => query,
+    }
+}
@@ -1,2 +1,10 @@
+pub mod api_key_service;
 pub mod brand_service;
+pub mod car_service;
+pub mod health_service;
+pub mod listing_service;
+pub mod model_service;
+pub mod post_migration_service;
 pub mod post_service;
+pub mod seller_service;
+pub mod version_service;
@@ -0,0 +1,398 @@
+use chrono::{DateTime, Utc};
+
+use crate::auth::principal::Principal;
+use crate::database::database::checkout;
+use crate::error::app_error::AppError;
+use crate::models::model_models::{CreateModel, Model, UpdateModel};
+use crate::schema::brands;
+use crate::schema::models::{self, dsl::*, BoxedQuery};
+use crate::service::brand_service::live_brand_ids;
+use crate::utils::model_columns::{get_column, ModelColumn};
+
+use diesel::dsl::count_star;
+use diesel::pg::Pg;
+use diesel::query_builder::QueryFragment;
+use diesel::r2d2::{ConnectionManager, Pool, PooledConnection};
+use diesel::sql_types;
+use diesel::{
+    AppearsOnTable, ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl, SelectableHelper,
+};
+use log::{error, info, warn};
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[allow(clippy::too_many_arguments)]
+pub fn get_models(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    parent_brand_id: Option<Uuid>,
+    offset: u32,
+    limit: u32,
+    sort_by: String,
+    sort_order: String,
+    filter_by: String,
+    filter_term: String,
+    include_deleted: bool,
+) -> Result<Vec<Model>, AppError> {
+    info!(
+        "Get all models of brand '{:?}' starting at '{}', limited to '{}', sort by '{}' order '{}', filter by '{}' term '{}', include deleted '{}'",
+        parent_brand_id, offset, limit, sort_by, sort_order, filter_by, filter_term, include_deleted
+    );
+
+    let mut query = models::table
+        .into_boxed()
+        .limit(limit as i64)
+        .offset(offset as i64);
+
+    if !include_deleted {
+        query = query
+            .filter(deleted_at.is_null())
+            .filter(brand_id.eq_any(live_brand_ids()));
+    }
+
+    if let Some(parent_brand_id) = parent_brand_id {
+        query = query.filter(brand_id.eq(parent_brand_id));
+    }
+
+    let sort_column: ModelColumn = get_column(sort_by.as_str());
+    query = match sort_column {
+        ModelColumn::Text(column) => sort_by_column(query, column, Some(sort_order)),
+        ModelColumn::NullableText(column) => sort_by_column(query, column, Some(sort_order)),
+        ModelColumn::Uuid(column) => sort_by_column(query, column, Some(sort_order)),
+    };
+
+    if !filter_by.is_empty() && !filter_term.is_empty() {
+        let filter_column: ModelColumn = get_column(filter_by.as_str());
+        query = match filter_column {
+            ModelColumn::Text(column) => query.filter(column.eq(filter_term)),
+            ModelColumn::NullableText(column) => query.filter(column.eq(filter_term)),
+            ModelColumn::Uuid(column) => query.filter(column.eq(filter_term.parse::<Uuid>()?)),
+        };
+    }
+
+    let model_list = query.load(&mut get_connection(&pool)?);
+
+    match model_list {
+        Ok(model_list) => Ok(model_list),
+        Err(err) => {
+            error!("Unable to retrieve models, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+pub fn get_model(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    model_id: Uuid,
+    include_deleted: bool,
+) -> Result<Model, AppError> {
+    info!(
+        "Get model with id: {}, include deleted '{}'",
+        model_id, include_deleted
+    );
+
+    let mut query = models::table
+        .select(Model::as_select())
+        .filter(id.eq(model_id))
+        .into_boxed();
+
+    if !include_deleted {
+        query = query
+            .filter(deleted_at.is_null())
+            .filter(brand_id.eq_any(live_brand_ids()));
+    }
+
+    let result = query.first(&mut get_connection(&pool)?);
+
+    match result {
+        Ok(result) => Ok(result),
+        Err(err) => {
+            error!("Unable to retrieve model, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+pub fn create_model(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    create_model: CreateModel,
+    principal: &Principal,
+) -> Result<Model, AppError> {
+    info!("Create model: {:?}", create_model);
+
+    let mut connection = get_connection(&pool)?;
+    ensure_brands_exist(&mut connection, vec![create_model.brand_id])?;
+
+    let new_model: Model = Model {
+        id: Uuid::new_v4(),
+        brand_id: create_model.brand_id,
+        name: create_model.name,
+        created_at: Utc::now(),
+        updated_at: None,
+        deleted_at: None,
+        created_by: principal.audit_name(),
+        updated_by: None,
+        deleted_by: None,
+    };
+
+    let created_model = diesel::insert_into(models)
+        .values(&new_model)
+        .returning(Model::as_returning())
+        .get_result(&mut connection);
+
+    match created_model {
+        Ok(model) => Ok(model),
+        Err(err) => {
+            error!("Unable to create model, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+pub fn create_models(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    new_models: Vec<CreateModel>,
+    principal: &Principal,
+) -> Result<Vec<Model>, AppError> {
+    info!("Create models: {:?}", new_models);
+
+    if new_models.is_empty() {
+        warn!("No models to create");
+        return Err(AppError::validation(
+            "body",
+            "must contain at least one model",
+        ));
+    }
+
+    let mut connection = get_connection(&pool)?;
+    ensure_brands_exist(
+        &mut connection,
+        new_models
+            .iter()
+            .map(|new_model| new_model.brand_id)
+            .collect(),
+    )?;
+
+    let mut model_entities: Vec<Model> = Vec::new();
+    let now: DateTime<Utc> = Utc::now();
+    let default_created_by = principal.audit_name();
+
+    for new_model in new_models {
+        model_entities.push(Model {
+            id: Uuid::new_v4(),
+            brand_id: new_model.brand_id,
+            name: new_model.name,
+            created_at: now,
+            updated_at: None,
+            deleted_at: None,
+            created_by: default_created_by.clone(),
+            updated_by: None,
+            deleted_by: None,
+        });
+    }
+
+    let result = diesel::insert_into(models)
+        .values(&model_entities)
+        .returning(Model::as_returning())
+        .get_results(&mut connection);
+
+    match result {
+        Ok(created) => Ok(created),
+        Err(err) => {
+            error!("Unable to create models, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+pub fn update_model(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    model_id: Uuid,
+    updated_model: UpdateModel,
+    principal: &Principal,
+) -> Result<usize, AppError> {
+    info!("Update model {} to {:?}", model_id, updated_model);
+
+    let mut connection = get_connection(&pool)?;
+    if let Some(new_brand_id) = updated_model.brand_id {
+        ensure_brands_exist(&mut connection, vec![new_brand_id])?;
+    }
+
+    let update_count = diesel::update(models)
+        .filter(id.eq(model_id))
+        .filter(deleted_at.is_null())
+        .set((
+            updated_model,
+            updated_at.eq(Utc::now()),
+            updated_by.eq(principal.audit_name()),
+        ))
+        .execute(&mut connection);
+
+    match update_count {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            error!("Unable to update models, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+pub fn delete_model(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    model_id: Uuid,
+    principal: &Principal,
+) -> Result<usize, AppError> {
+    info!("Delete model with id: {}", model_id);
+
+    let delete_count = diesel::update(models)
+        .filter(id.eq(model_id))
+        .filter(deleted_at.is_null())
+        .set((
+            deleted_at.eq(Utc::now()),
+            deleted_by.eq(principal.audit_name()),
+        ))
+        .execute(&mut get_connection(&pool)?);
+
+    match delete_count {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            error!("Unable to delete model, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+pub fn delete_models(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    models_ids: Vec<Uuid>,
+    principal: &Principal,
+) -> Result<usize, AppError> {
+    info!("Delete models with ids: {:?}", models_ids);
+
+    let delete_count = diesel::update(models)
+        .filter(id.eq_any(models_ids))
+        .filter(deleted_at.is_null())
+        .set((
+            deleted_at.eq(Utc::now()),
+            deleted_by.eq(principal.audit_name()),
+        ))
+        .execute(&mut get_connection(&pool)?);
+
+    match delete_count {
+        Ok(count) => {
+            info!("Models delete count: {}", count);
+            Ok(count)
+        }
+        Err(err) => {
+            error!("Unable to delete models, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+pub fn restore_model(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    model_id: Uuid,
+    principal: &Principal,
+) -> Result<usize, AppError> {
+    info!("Restore model with id: {}", model_id);
+
+    let restore_count = diesel::update(models)
+        .filter(id.eq(model_id))
+        .filter(deleted_at.is_not_null())
+        .set((
+            deleted_at.eq(None::<DateTime<Utc>>),
+            deleted_by.eq(None::<String>),
+            updated_at.eq(Utc::now()),
+            updated_by.eq(principal.audit_name()),
+        ))
+        .execute(&mut get_connection(&pool)?);
+
+    match restore_count {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            error!("Unable to restore model, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+/// Permanently removes a model, which must have been soft deleted first.
+pub fn purge_model(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    model_id: Uuid,
+) -> Result<usize, AppError> {
+    info!("Purge model with id: {}", model_id);
+
+    let purge_count = diesel::delete(models)
+        .filter(id.eq(model_id))
+        .filter(deleted_at.is_not_null())
+        .execute(&mut get_connection(&pool)?);
+
+    match purge_count {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            error!("Unable to purge model, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+/// Ids of the models that are not soft deleted, nor is their brand.
+pub fn live_model_ids() -> BoxedQuery<'static, Pg, sql_types::Uuid> {
+    models::table
+        .select(id)
+        .filter(deleted_at.is_null())
+        .filter(brand_id.eq_any(live_brand_ids()))
+        .into_boxed()
+}
+
+fn ensure_brands_exist(
+    connection: &mut PgConnection,
+    mut brands_ids: Vec<Uuid>,
+) -> Result<(), AppError> {
+    brands_ids.sort();
+    brands_ids.dedup();
+
+    let found: i64 = brands::table
+        .filter(brands::id.eq_any(&brands_ids))
+        .filter(brands::id.eq_any(live_brand_ids()))
+        .select(count_star())
+        .get_result(connection)?;
+
+    if found as usize != brands_ids.len() {
+        warn!("Unknown brand in: {:?}", brands_ids);
+        return Err(AppError::validation(
+            "brand_id",
+            format!("unknown brand in: {:?}", brands_ids),
+        ));
+    }
+
+    Ok(())
+}
+
+fn get_connection(
+    pool: &Pool<ConnectionManager<PgConnection>>,
+) -> Result<PooledConnection<ConnectionManager<PgConnection>>, AppError> {
+    let connection = checkout(pool, "model").map_err(|err| {
+        error!("Unable to connect to database, error: {}", err);
+        AppError::from(err)
+    })?;
+
+    Ok(connection)
+}
+
+// https://stackoverflow.com/a/62029781
+fn sort_by_column<U>(
+    query: BoxedQuery<'static, Pg>,
+    column: U,
+    sort_dir: Option<String>,
+) -> BoxedQuery<'static, Pg>
+where
+    U: 'static + Send + ExpressionMethods + QueryFragment<Pg> + AppearsOnTable<models::table>,
+{
+    match sort_dir.as_deref() {
+        Some("asc") => query.order_by(column.asc()),
+        Some("desc") => query.order_by(column.desc()),
+        _ => query,
+    }
+}
@@ -0,0 +1,343 @@
+use chrono::{DateTime, Utc};
+
+use crate::auth::principal::Principal;
+use crate::database::database::checkout;
+use crate::error::app_error::AppError;
+use crate::models::car_models::Car;
+use crate::models::listing_models::Listing;
+use crate::models::post_migration_models::{
+    MigratedPost, PostListing, PostMigrationReport, SkippedPost,
+};
+use crate::models::post_models::Post;
+use crate::schema::{brands, cars, listings, models, post_listings, posts, sellers, versions};
+
+use diesel::dsl::{exists, not};
+use diesel::r2d2::{ConnectionManager, Pool, PooledConnection};
+use diesel::result::Error as DieselError;
+use diesel::sql_types::Text;
+use diesel::{
+    define_sql_function, Connection, ExpressionMethods, PgConnection, QueryDsl, QueryResult,
+    RunQueryDsl, SelectableHelper,
+};
+use log::{error, info, warn};
+use std::sync::Arc;
+use uuid::Uuid;
+
+define_sql_function!(fn lower(x: Text) -> Text);
+
+enum PostOutcome {
+    Migrated(MigratedPost),
+    Unmatched(String),
+    Ambiguous(String),
+}
+
+/// Why a post's transaction was rolled back: on purpose, keeping the outcome to report, or on a
+/// database error.
+enum Abort {
+    Rollback(PostOutcome),
+    Database(DieselError),
+}
+
+impl From<DieselError> for Abort {
+    fn from(err: DieselError) -> Self {
+        Abort::Database(err)
+    }
+}
+
+enum Lookup<T> {
+    Missing,
+    One(T),
+    Many(usize),
+}
+
+impl<T> From<Vec<T>> for Lookup<T> {
+    fn from(mut rows: Vec<T>) -> Self {
+        match rows.len() {
+            0 => Lookup::Missing,
+            1 => Lookup::One(rows.remove(0)),
+            count => Lookup::Many(count),
+        }
+    }
+}
+
+/// Migrates every post not yet migrated into the catalog and a listing, one transaction per post.
+///
+/// Live catalog rows are matched case-insensitively, soft deleted ones are ignored. Missing ones
+/// are never created, as posts lack the brand images and door count the catalog requires, so
+/// those posts are reported unmatched. The seller is the live one named after the post author, falling back to
+/// `default_seller_id`. On a dry run every transaction is rolled back, so the report only
+/// describes what would happen.
+pub fn migrate_posts(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    dry_run: bool,
+    default_seller_id: Option<Uuid>,
+    principal: &Principal,
+) -> Result<PostMigrationReport, AppError> {
+    info!(
+        "Migrate posts to listings, dry run '{}', default seller '{:?}'",
+        dry_run, default_seller_id
+    );
+
+    let mut connection = get_connection(&pool)?;
+
+    let pending_posts: Vec<Post> = posts::table
+        .filter(posts::deleted_at.is_null())
+        .filter(not(exists(
+            post_listings::table.filter(post_listings::post_id.eq(posts::id)),
+        )))
+        .select(Post::as_select())
+        .load(&mut connection)
+        .map_err(|err| {
+            error!("Unable to retrieve posts to migrate, error: {}", err);
+            err
+        })?;
+
+    let mut report = PostMigrationReport {
+        dry_run,
+        total: pending_posts.len(),
+        ..Default::default()
+    };
+    let now: DateTime<Utc> = Utc::now();
+    let migrated_by = principal.audit_name();
+
+    for post in pending_posts {
+        let post_id = post.id;
+
+        let result = connection.transaction(|conn| {
+            let outcome = migrate_post(conn, post, default_seller_id, now, &migrated_by)?;
+            if !dry_run && matches!(outcome, PostOutcome::Migrated(_)) {
+                Ok(outcome)
+            } else {
+                Err(Abort::Rollback(outcome))
+            }
+        });
+
+        match result {
+            Ok(outcome) | Err(Abort::Rollback(outcome)) => match outcome {
+                PostOutcome::Migrated(mut migrated) => {
+                    if dry_run {
+                        migrated.car_id = None;
+                        migrated.listing_id = None;
+                    }
+                    report.migrated.push(migrated);
+                }
+                PostOutcome::Unmatched(reason) => {
+                    warn!("Post {} not migrated: {}", post_id, reason);
+                    report.unmatched.push(SkippedPost { post_id, reason });
+                }
+                PostOutcome::Ambiguous(reason) => {
+                    warn!("Post {} not migrated: {}", post_id, reason);
+                    report.ambiguous.push(SkippedPost { post_id, reason });
+                }
+            },
+            Err(Abort::Database(err)) => {
+                error!("Unable to migrate post {}, error: {}", post_id, err);
+                report.failed.push(SkippedPost {
+                    post_id,
+                    reason: err.to_string(),
+                });
+            }
+        }
+    }
+
+    info!(
+        "Posts migration finished, migrated '{}', unmatched '{}', ambiguous '{}', failed '{}'",
+        report.migrated.len(),
+        report.unmatched.len(),
+        report.ambiguous.len(),
+        report.failed.len()
+    );
+
+    Ok(report)
+}
+
+fn migrate_post(
+    conn: &mut PgConnection,
+    post: Post,
+    default_seller_id: Option<Uuid>,
+    now: DateTime<Utc>,
+    migrated_by: &str,
+) -> QueryResult<PostOutcome> {
+    let Ok(price) = i32::try_from(post.price) else {
+        return Ok(PostOutcome::Unmatched(format!(
+            "price '{}' is too large for a listing",
+            post.price
+        )));
+    };
+
+    let (seller_id, phone, email) = match find_seller(conn, &post.author, default_seller_id)? {
+        Lookup::One(seller) => seller,
+        Lookup::Missing => {
+            return Ok(PostOutcome::Unmatched(format!(
+                "no seller named '{}' and no default seller",
+                post.author
+            )))
+        }
+        Lookup::Many(count) => {
+            return Ok(PostOutcome::Ambiguous(format!(
+                "{} sellers named '{}'",
+                count, post.author
+            )))
+        }
+    };
+
+    let brand_id = match Lookup::from(
+        brands::table
+            .filter(brands::deleted_at.is_null())
+            .filter(lower(brands::name).eq(lower(post.brand.trim())))
+            .select(brands::id)
+            .load::<Uuid>(conn)?,
+    ) {
+        Lookup::One(brand_id) => brand_id,
+        Lookup::Missing => {
+            return Ok(PostOutcome::Unmatched(format!(
+                "no brand named '{}'",
+                post.brand
+            )))
+        }
+        Lookup::Many(count) => {
+            return Ok(PostOutcome::Ambiguous(format!(
+                "{} brands named '{}'",
+                count, post.brand
+            )))
+        }
+    };
+
+    let model_id = match Lookup::from(
+        models::table
+            .filter(models::deleted_at.is_null())
+            .filter(models::brand_id.eq(brand_id))
+            .filter(lower(models::name).eq(lower(post.model.trim())))
+            .select(models::id)
+            .load::<Uuid>(conn)?,
+    ) {
+        Lookup::One(model_id) => model_id,
+        Lookup::Missing => {
+            return Ok(PostOutcome::Unmatched(format!(
+                "no model named '{}' for brand '{}'",
+                post.model, post.brand
+            )))
+        }
+        Lookup::Many(count) => {
+            return Ok(PostOutcome::Ambiguous(format!(
+                "{} models named '{}' for brand '{}'",
+                count, post.model, post.brand
+            )))
+        }
+    };
+
+    let version_id = match Lookup::from(
+        versions::table
+            .filter(versions::deleted_at.is_null())
+            .filter(versions::model_id.eq(model_id))
+            .filter(lower(versions::name).eq(lower(post.version.trim())))
+            .filter(lower(versions::engine).eq(lower(post.engine.trim())))
+            .filter(lower(versions::transmission).eq(lower(post.transmission.trim())))
+            .filter(lower(versions::body).eq(lower(post.body.trim())))
+            .filter(versions::year.eq(post.year))
+            .select(versions::id)
+            .load::<Uuid>(conn)?,
+    ) {
+        Lookup::One(version_id) => version_id,
+        Lookup::Missing => {
+            return Ok(PostOutcome::Unmatched(format!(
+                "no version named '{}' ({}) for model '{}'",
+                post.version, post.year, post.model
+            )))
+        }
+        Lookup::Many(count) => {
+            return Ok(PostOutcome::Ambiguous(format!(
+                "{} versions named '{}' ({}) for model '{}'",
+                count, post.version, post.year, post.model
+            )))
+        }
+    };
+
+    let car = Car {
+        id: Uuid::new_v4(),
+        version_id,
+        mileage: post.mileage,
+        color: post.color,
+        armored: post.armored,
+        owner: post.author,
+        created_at: now,
+        updated_at: None,
+        deleted_at: None,
+        created_by: migrated_by.to_string(),
+        updated_by: None,
+        deleted_by: None,
+    };
+    diesel::insert_into(cars::table)
+        .values(&car)
+        .execute(conn)?;
+
+    let listing = Listing {
+        id: Uuid::new_v4(),
+        car_id: car.id,
+        seller_id,
+        price,
+        exchange: post.exchange,
+        phone,
+        email,
+        created_at: now,
+        updated_at: None,
+        deleted_at: None,
+        created_by: migrated_by.to_string(),
+        updated_by: None,
+        deleted_by: None,
+    };
+    diesel::insert_into(listings::table)
+        .values(&listing)
+        .execute(conn)?;
+
+    diesel::insert_into(post_listings::table)
+        .values(&PostListing {
+            post_id: post.id,
+            listing_id: listing.id,
+            migrated_at: now,
+            migrated_by: migrated_by.to_string(),
+        })
+        .execute(conn)?;
+
+    Ok(PostOutcome::Migrated(MigratedPost {
+        post_id: post.id,
+        car_id: Some(car.id),
+        listing_id: Some(listing.id),
+    }))
+}
+
+fn find_seller(
+    conn: &mut PgConnection,
+    author: &str,
+    default_seller_id: Option<Uuid>,
+) -> QueryResult<Lookup<(Uuid, String, String)>> {
+    let by_name = Lookup::from(
+        sellers::table
+            .filter(lower(sellers::name).eq(lower(author.trim())))
+            .filter(sellers::deleted_at.is_null())
+            .select((sellers::id, sellers::phone, sellers::email))
+            .load::<(Uuid, String, String)>(conn)?,
+    );
+
+    match (by_name, default_seller_id) {
+        (Lookup::Missing, Some(default_seller_id)) => Ok(Lookup::from(
+            sellers::table
+                .filter(sellers::id.eq(default_seller_id))
+                .filter(sellers::deleted_at.is_null())
+                .select((sellers::id, sellers::phone, sellers::email))
+                .load::<(Uuid, String, String)>(conn)?,
+        )),
+        (by_name, _) => Ok(by_name),
+    }
+}
+
+fn get_connection(
+    pool: &Pool<ConnectionManager<PgConnection>>,
+) -> Result<PooledConnection<ConnectionManager<PgConnection>>, AppError> {
+    let connection = checkout(pool, "post_migration").map_err(|err| {
+        error!("Unable to connect to database, error: {}", err);
+        AppError::from(err)
+    })?;
+
+    Ok(connection)
+}
@@ -1,20 +1,29 @@
+use chrono::{DateTime, Utc};
 use diesel::pg::Pg;
 use diesel::query_builder::QueryFragment;
 
+use crate::auth::principal::Principal;
+use crate::auth::role::Role;
+use crate::database::database::checkout;
+use crate::error::app_error::AppError;
 use crate::models::post_models::{CreatePost, Post, UpdatePost};
 use crate::schema::posts::{self, dsl::*, BoxedQuery};
 use crate::utils::post_columns::{get_column, PostColumn};
 
+use diesel::dsl::exists;
 use diesel::r2d2::{ConnectionManager, Pool, PooledConnection};
 use diesel::{
-    AppearsOnTable, ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl, SelectableHelper,
+    AppearsOnTable, BoolExpressionMethods, Connection, ExpressionMethods, PgConnection, QueryDsl,
+    RunQueryDsl, SelectableHelper,
 };
-use log::{error, info};
-use std::error::Error;
+use log::{error, info, warn};
 use std::sync::Arc;
+use tracing::field::Empty;
+use tracing::{instrument, Span};
 
 use uuid::Uuid;
 
+#[instrument(skip(pool), fields(rows = Empty))]
 pub fn get_posts(
     pool: Arc<Pool<ConnectionManager<PgConnection>>>,
     offset: u32,
@@ -23,7 +32,7 @@ pub fn get_posts(
     sort_order: String,
     filter_by: String,
     filter_term: String,
-) -> Result<Vec<Post>, Box<dyn Error>> {
+) -> Result<Vec<Post>, AppError> {
     info!(
         "Get all posts starting at '{}', limited to '{}', sort by '{}' order '{}', filter by '{}' term '{}'",
         offset, limit, sort_by, sort_order, filter_by, filter_term
@@ -32,6 +41,7 @@ pub fn get_posts(
     let mut query = posts::table
         .into_boxed()
         .filter(published.eq(true))
+        .filter(deleted_at.is_null())
         .limit(limit as i64)
         .offset(offset as i64);
 
@@ -41,6 +51,9 @@ pub fn get_posts(
         PostColumn::Text(column) => sort_by_column(query, column, Some(sort_order)),
         PostColumn::Bool(column) => sort_by_column(query, column, Some(sort_order)),
         PostColumn::BigInteger(column) => sort_by_column(query, column, Some(sort_order)),
+        PostColumn::NullableText(column) => sort_by_column(query, column, Some(sort_order)),
+        PostColumn::Timestamp(column) => sort_by_column(query, column, Some(sort_order)),
+        PostColumn::NullableTimestamp(column) => sort_by_column(query, column, Some(sort_order)),
     };
 
     if !filter_by.is_empty() && !filter_term.is_empty() {
@@ -50,13 +63,23 @@ pub fn get_posts(
             PostColumn::Text(column) => query.filter(column.eq(filter_term)),
             PostColumn::Bool(column) => query.filter(column.eq(filter_term.parse::<bool>()?)),
             PostColumn::BigInteger(column) => query.filter(column.eq(filter_term.parse::<i64>()?)),
+            PostColumn::NullableText(column) => query.filter(column.eq(filter_term)),
+            PostColumn::Timestamp(column) => {
+                query.filter(column.eq(filter_term.parse::<DateTime<Utc>>()?))
+            }
+            PostColumn::NullableTimestamp(column) => {
+                query.filter(column.eq(filter_term.parse::<DateTime<Utc>>()?))
+            }
         };
     }
 
     let post_list = query.load(&mut get_connection(&pool)?);
 
     match post_list {
-        Ok(post_list) => Ok(post_list),
+        Ok(post_list) => {
+            Span::current().record("rows", post_list.len());
+            Ok(post_list)
+        }
         Err(err) => {
             error!("Unable to retrieve posts, error: {}", err);
             Err(err.into())
@@ -64,19 +87,84 @@ pub fn get_posts(
     }
 }
 
+/// Every published post, oldest first, for exports.
+#[instrument(skip(pool), fields(rows = Empty))]
+pub fn get_published_posts(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+) -> Result<Vec<Post>, AppError> {
+    info!("Get every published post");
+
+    let post_list = posts
+        .filter(published.eq(true))
+        .filter(deleted_at.is_null())
+        .order_by((created_at.asc(), id.asc()))
+        .select(Post::as_select())
+        .load(&mut get_connection(&pool)?);
+
+    match post_list {
+        Ok(post_list) => {
+            Span::current().record("rows", post_list.len());
+            Ok(post_list)
+        }
+        Err(err) => {
+            error!("Unable to retrieve published posts, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+#[instrument(skip(pool), fields(rows = Empty))]
+pub fn count_published_posts(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+) -> Result<i64, AppError> {
+    info!("Count published posts");
+
+    let count = posts
+        .filter(published.eq(true))
+        .filter(deleted_at.is_null())
+        .count()
+        .get_result(&mut get_connection(&pool)?);
+
+    match count {
+        Ok(count) => {
+            Span::current().record("rows", count);
+            Ok(count)
+        }
+        Err(err) => {
+            error!("Unable to count published posts, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+#[instrument(skip(pool), fields(rows = Empty))]
+/// Unpublished posts are visible to their author and admins only, like `get_posts` hides them.
 pub fn get_post(
     pool: Arc<Pool<ConnectionManager<PgConnection>>>,
     post_id: Uuid,
-) -> Result<Post, Box<dyn Error>> {
+    principal: Option<&Principal>,
+) -> Result<Post, AppError> {
     info!("Get post with id: {}", post_id);
 
-    let result = posts
+    let mut query = posts
         .filter(id.eq(post_id))
+        .filter(deleted_at.is_null())
         .select(Post::as_select())
-        .first(&mut get_connection(&pool)?);
+        .into_boxed();
+    match principal {
+        Some(principal) if principal.has_role(Role::Admin) => {}
+        Some(principal) => {
+            query = query.filter(published.eq(true).or(author.eq(principal.subject.clone())))
+        }
+        None => query = query.filter(published.eq(true)),
+    }
+    let result = query.first(&mut get_connection(&pool)?);
 
     match result {
-        Ok(result) => Ok(result),
+        Ok(result) => {
+            Span::current().record("rows", 1);
+            Ok(result)
+        }
         Err(err) => {
             error!("Unable to retrieve post, error: {}", err);
             Err(err.into())
@@ -84,12 +172,16 @@ pub fn get_post(
     }
 }
 
+#[instrument(skip(pool, principal), fields(caller = %principal.subject, rows = Empty))]
 pub fn create_post(
     pool: Arc<Pool<ConnectionManager<PgConnection>>>,
     create_post: CreatePost,
-) -> Result<Post, Box<dyn Error>> {
+    principal: &Principal,
+) -> Result<Post, AppError> {
     info!("Create post: {:?}", create_post);
 
+    let post_author = resolve_author(create_post.author, principal)?;
+
     let new_post: Post = Post {
         id: Uuid::new_v4(),
         brand: create_post.brand,
@@ -105,8 +197,14 @@ pub fn create_post(
         exchange: create_post.exchange,
         price: create_post.price,
         thumbnail_url: create_post.thumbnail_url,
-        author: create_post.author,
+        author: post_author,
         published: true,
+        created_at: Utc::now(),
+        updated_at: None,
+        deleted_at: None,
+        created_by: principal.audit_name(),
+        updated_by: None,
+        deleted_by: None,
     };
 
     let created_post = diesel::insert_into(posts)
@@ -115,7 +213,10 @@ pub fn create_post(
         .get_result(&mut get_connection(&pool)?);
 
     match created_post {
-        Ok(post) => Ok(post),
+        Ok(post) => {
+            Span::current().record("rows", 1);
+            Ok(post)
+        }
         Err(err) => {
             error!("Unable to create post, error: {}", err);
             Err(err.into())
@@ -123,20 +224,29 @@ pub fn create_post(
     }
 }
 
+#[instrument(skip(pool, principal), fields(caller = %principal.subject, rows = Empty))]
 pub fn create_posts(
     pool: Arc<Pool<ConnectionManager<PgConnection>>>,
     new_posts: Vec<CreatePost>,
-) -> Result<Vec<Post>, Box<dyn Error>> {
+    principal: &Principal,
+) -> Result<Vec<Post>, AppError> {
     info!("Create posts: {:?}", new_posts);
 
     if new_posts.is_empty() {
         info!("No posts to create");
-        return Err("No posts to create".into());
+        return Err(AppError::validation(
+            "body",
+            "must contain at least one post",
+        ));
     }
 
     let mut post_entities: Vec<Post> = Vec::new();
+    let now: DateTime<Utc> = Utc::now();
+    let default_created_by = principal.audit_name();
 
     for new_post in new_posts {
+        let post_author = resolve_author(new_post.author, principal)?;
+
         post_entities.push(Post {
             id: Uuid::new_v4(),
             brand: new_post.brand,
@@ -152,8 +262,14 @@ pub fn create_posts(
             exchange: new_post.exchange,
             price: new_post.price,
             thumbnail_url: new_post.thumbnail_url,
-            author: new_post.author,
+            author: post_author,
             published: true,
+            created_at: now,
+            updated_at: None,
+            deleted_at: None,
+            created_by: default_created_by.clone(),
+            updated_by: None,
+            deleted_by: None,
         });
     }
 
@@ -163,7 +279,10 @@ pub fn create_posts(
         .get_results(&mut get_connection(&pool)?);
 
     match result {
-        Ok(created) => Ok(created),
+        Ok(created) => {
+            Span::current().record("rows", created.len());
+            Ok(created)
+        }
         Err(err) => {
             error!("Unable to create posts, error: {}", err);
             Err(err.into())
@@ -171,39 +290,48 @@ pub fn create_posts(
     }
 }
 
+#[instrument(skip(pool, principal), fields(caller = %principal.subject, rows = Empty))]
 pub fn update_post(
     pool: Arc<Pool<ConnectionManager<PgConnection>>>,
     post_id: Uuid,
     updated_post: UpdatePost,
-) -> Result<usize, Box<dyn Error>> {
+    principal: &Principal,
+) -> Result<usize, AppError> {
     info!("Update post {} to {:?}", post_id, updated_post);
 
-    let updated_post: Post = Post {
-        id: post_id,
-        brand: updated_post.brand,
-        model: updated_post.model,
-        version: updated_post.version,
-        engine: updated_post.engine,
-        transmission: updated_post.transmission,
-        year: updated_post.year,
-        mileage: updated_post.mileage,
-        color: updated_post.color,
-        body: updated_post.body,
-        armored: updated_post.armored,
-        exchange: updated_post.exchange,
-        price: updated_post.price,
-        thumbnail_url: updated_post.thumbnail_url,
-        author: updated_post.author,
-        published: updated_post.published,
-    };
+    if updated_post.is_empty() {
+        info!("No post fields to update");
+        return Err(AppError::validation(
+            "body",
+            "must contain at least one field to update",
+        ));
+    }
 
-    let update_count = diesel::update(posts)
+    if let Some(new_author) = &updated_post.author {
+        resolve_author(Some(new_author.clone()), principal)?;
+    }
+
+    let mut connection = get_connection(&pool)?;
+    let mut query = diesel::update(posts)
         .filter(id.eq(post_id))
-        .set(updated_post)
-        .execute(&mut get_connection(&pool)?);
+        .filter(deleted_at.is_null())
+        .set((
+            &updated_post,
+            updated_at.eq(Utc::now()),
+            updated_by.eq(principal.audit_name()),
+        ))
+        .into_boxed();
+    if !principal.has_role(Role::Admin) {
+        query = query.filter(author.eq(principal.subject.clone()));
+    }
+    let update_count = query.execute(&mut connection);
 
     match update_count {
-        Ok(count) => Ok(count),
+        Ok(0) => unmodified(&mut connection, &[post_id], principal),
+        Ok(count) => {
+            Span::current().record("rows", count);
+            Ok(count)
+        }
         Err(err) => {
             error!("Unable to update posts, error: {}", err);
             Err(err.into())
@@ -211,18 +339,34 @@ pub fn update_post(
     }
 }
 
+#[instrument(skip(pool, principal), fields(caller = %principal.subject, rows = Empty))]
 pub fn delete_post(
     pool: Arc<Pool<ConnectionManager<PgConnection>>>,
     post_id: Uuid,
-) -> Result<usize, Box<dyn Error>> {
+    principal: &Principal,
+) -> Result<usize, AppError> {
     info!("Delete post with id: {}", post_id);
 
-    let delete_count = diesel::delete(posts)
+    let mut connection = get_connection(&pool)?;
+    let mut query = diesel::update(posts)
         .filter(id.eq(post_id))
-        .execute(&mut get_connection(&pool)?);
+        .filter(deleted_at.is_null())
+        .set((
+            deleted_at.eq(Utc::now()),
+            deleted_by.eq(principal.audit_name()),
+        ))
+        .into_boxed();
+    if !principal.has_role(Role::Admin) {
+        query = query.filter(author.eq(principal.subject.clone()));
+    }
+    let delete_count = query.execute(&mut connection);
 
     match delete_count {
-        Ok(count) => Ok(count),
+        Ok(0) => unmodified(&mut connection, &[post_id], principal),
+        Ok(count) => {
+            Span::current().record("rows", count);
+            Ok(count)
+        }
         Err(err) => {
             error!("Unable to delete post, error: {}", err);
             Err(err.into())
@@ -230,40 +374,109 @@ pub fn delete_post(
     }
 }
 
+#[instrument(skip(pool, principal), fields(caller = %principal.subject, rows = Empty))]
 pub fn delete_posts(
     pool: Arc<Pool<ConnectionManager<PgConnection>>>,
     post_ids: Vec<Uuid>,
-) -> Result<usize, Box<dyn Error>> {
+    principal: &Principal,
+) -> Result<usize, AppError> {
     info!("Delete posts with ids: {:?}", post_ids);
 
-    let delete_count = diesel::delete(posts)
-        .filter(id.eq_any(post_ids))
-        .execute(&mut get_connection(&pool)?);
+    let mut connection = get_connection(&pool)?;
+    // Posts of other authors are left live by the UPDATE, finding any afterwards rolls the
+    // whole batch back.
+    let delete_count = connection.transaction::<_, AppError, _>(|conn| {
+        let mut query = diesel::update(posts)
+            .filter(id.eq_any(&post_ids))
+            .filter(deleted_at.is_null())
+            .set((
+                deleted_at.eq(Utc::now()),
+                deleted_by.eq(principal.audit_name()),
+            ))
+            .into_boxed();
+        if !principal.has_role(Role::Admin) {
+            query = query.filter(author.eq(principal.subject.clone()));
+        }
+        let count = query.execute(conn)?;
+        unmodified(conn, &post_ids, principal)?;
+        Ok(count)
+    });
 
     match delete_count {
         Ok(count) => {
+            Span::current().record("rows", count);
             info!("Posts delete count: {}", count);
             Ok(count)
         }
+        Err(err @ AppError::Forbidden(_)) => Err(err),
         Err(err) => {
             error!("Unable to delete posts, error: {}", err);
-            Err(err.into())
+            Err(err)
+        }
+    }
+}
+
+/// Posts are authored by the caller, only admins may post on behalf of someone else.
+fn resolve_author(requested: Option<String>, principal: &Principal) -> Result<String, AppError> {
+    match requested {
+        Some(requested) if requested != principal.subject && !principal.has_role(Role::Admin) => {
+            warn!(
+                "'{}' tried to author a post as '{}'",
+                principal.subject, requested
+            );
+            Err(AppError::Forbidden(String::from(
+                "Posts can only be authored by the authenticated user",
+            )))
         }
+        Some(requested) => Ok(requested),
+        None => Ok(principal.subject.clone()),
     }
 }
 
+/// Authors modify only their own posts, so a live post left untouched is someone else's (403),
+/// otherwise nothing matched (0 rows, a 404).
+fn unmodified(
+    connection: &mut PgConnection,
+    post_ids: &[Uuid],
+    principal: &Principal,
+) -> Result<usize, AppError> {
+    let found: bool = diesel::select(exists(
+        posts
+            .filter(id.eq_any(post_ids))
+            .filter(deleted_at.is_null()),
+    ))
+    .get_result(connection)
+    .map_err(|err| {
+        error!("Unable to check posts ownership, error: {}", err);
+        AppError::from(err)
+    })?;
+
+    if found {
+        warn!(
+            "'{}' tried to modify post(s) of other authors",
+            principal.subject
+        );
+        return Err(AppError::Forbidden(String::from(
+            "Only the author or an admin may modify a post",
+        )));
+    }
+
+    Ok(0)
+}
+
 fn get_connection(
     pool: &Pool<ConnectionManager<PgConnection>>,
-) -> Result<PooledConnection<ConnectionManager<PgConnection>>, Box<dyn Error>> {
-    let connection = pool.get().map_err(|err| {
+) -> Result<PooledConnection<ConnectionManager<PgConnection>>, AppError> {
+    let connection = checkout(pool, "post").map_err(|err| {
         error!("Unable to connect to database, error: {}", err);
-        Box::new(err) as Box<dyn Error>
+        AppError::from(err)
     })?;
 
     Ok(connection)
 }
 
 // https://stackoverflow.com/a/62029781
+#[allow(clippy::multiple_bound_locations, clippy::option_as_ref_deref)]
 fn sort_by_column<U: 'static + std::marker::Send>(
     query: BoxedQuery<'static, Pg>,
     column: U,
@@ -0,0 +1,406 @@
+use chrono::{DateTime, Utc};
+
+use crate::auth::principal::Principal;
+use crate::auth::role::Role;
+use crate::database::database::checkout;
+use crate::error::app_error::AppError;
+use crate::models::seller_models::{
+    BusinessHours, CreateSeller, Seller, SellerDetails, UpdateSeller,
+};
+use crate::schema::sellers::{self, dsl::*, BoxedQuery};
+use crate::utils::contact_validation::parse_zip_code;
+use crate::utils::seller_columns::{get_column, SellerColumn};
+
+use diesel::dsl::exists;
+use diesel::pg::Pg;
+use diesel::query_builder::QueryFragment;
+use diesel::r2d2::{ConnectionManager, Pool, PooledConnection};
+use diesel::{
+    AppearsOnTable, ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl, SelectableHelper,
+};
+use log::{error, info, warn};
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[allow(clippy::too_many_arguments)]
+pub fn get_sellers(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    offset: u32,
+    limit: u32,
+    sort_by: String,
+    sort_order: String,
+    filter_by: String,
+    filter_term: String,
+    include_deleted: bool,
+) -> Result<Vec<SellerDetails>, AppError> {
+    info!(
+        "Get all sellers starting at '{}', limited to '{}', sort by '{}' order '{}', filter by '{}' term '{}', include deleted '{}'",
+        offset, limit, sort_by, sort_order, filter_by, filter_term, include_deleted
+    );
+
+    let mut query = sellers::table
+        .into_boxed()
+        .limit(limit as i64)
+        .offset(offset as i64);
+
+    if !include_deleted {
+        query = query.filter(deleted_at.is_null());
+    }
+
+    let sort_column: SellerColumn = get_column(sort_by.as_str());
+    query = match sort_column {
+        SellerColumn::Integer(column) => sort_by_column(query, column, Some(sort_order)),
+        SellerColumn::Text(column) => sort_by_column(query, column, Some(sort_order)),
+        SellerColumn::NullableText(column) => sort_by_column(query, column, Some(sort_order)),
+    };
+
+    if !filter_by.is_empty() && !filter_term.is_empty() {
+        let filter_column: SellerColumn = get_column(filter_by.as_str());
+        query = match filter_column {
+            SellerColumn::Integer(column) => query.filter(column.eq(filter_term.parse::<i32>()?)),
+            SellerColumn::Text(column) => query.filter(column.eq(filter_term)),
+            SellerColumn::NullableText(column) => query.filter(column.eq(filter_term)),
+        };
+    }
+
+    let seller_list = query
+        .select(Seller::as_select())
+        .load(&mut get_connection(&pool)?);
+
+    match seller_list {
+        Ok(seller_list) => Ok(seller_list.into_iter().map(SellerDetails::from).collect()),
+        Err(err) => {
+            error!("Unable to retrieve sellers, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+pub fn get_seller(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    seller_id: Uuid,
+    include_deleted: bool,
+) -> Result<SellerDetails, AppError> {
+    info!(
+        "Get seller with id: {}, include deleted '{}'",
+        seller_id, include_deleted
+    );
+
+    let mut query = sellers::table
+        .select(Seller::as_select())
+        .filter(id.eq(seller_id))
+        .into_boxed();
+
+    if !include_deleted {
+        query = query.filter(deleted_at.is_null());
+    }
+
+    let result = query.first(&mut get_connection(&pool)?);
+
+    match result {
+        Ok(result) => Ok(SellerDetails::from(result)),
+        Err(err) => {
+            error!("Unable to retrieve seller, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+pub fn create_seller(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    create_seller: CreateSeller,
+    principal: &Principal,
+) -> Result<SellerDetails, AppError> {
+    info!("Create seller: {:?}", create_seller);
+
+    let new_seller = to_entity(create_seller, Utc::now(), principal.audit_name())?;
+
+    let created_seller = diesel::insert_into(sellers)
+        .values(&new_seller)
+        .returning(Seller::as_returning())
+        .get_result(&mut get_connection(&pool)?);
+
+    match created_seller {
+        Ok(seller) => Ok(SellerDetails::from(seller)),
+        Err(err) => {
+            error!("Unable to create seller, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+pub fn create_sellers(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    new_sellers: Vec<CreateSeller>,
+    principal: &Principal,
+) -> Result<Vec<SellerDetails>, AppError> {
+    info!("Create sellers: {:?}", new_sellers);
+
+    if new_sellers.is_empty() {
+        warn!("No sellers to create");
+        return Err(AppError::validation(
+            "body",
+            "must contain at least one seller",
+        ));
+    }
+
+    let now: DateTime<Utc> = Utc::now();
+    let default_created_by = principal.audit_name();
+
+    let seller_entities = new_sellers
+        .into_iter()
+        .map(|new_seller| to_entity(new_seller, now, default_created_by.clone()))
+        .collect::<Result<Vec<Seller>, AppError>>()?;
+
+    let result = diesel::insert_into(sellers)
+        .values(&seller_entities)
+        .returning(Seller::as_returning())
+        .get_results(&mut get_connection(&pool)?);
+
+    match result {
+        Ok(created) => Ok(created.into_iter().map(SellerDetails::from).collect()),
+        Err(err) => {
+            error!("Unable to create sellers, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+pub fn update_seller(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    seller_id: Uuid,
+    updated_seller: UpdateSeller,
+    principal: &Principal,
+) -> Result<usize, AppError> {
+    info!("Update seller {} to {:?}", seller_id, updated_seller);
+
+    let zip_code = parse_zip_code(&updated_seller.address.zip_code).ok_or_else(|| {
+        AppError::validation(
+            "address.zip_code",
+            format!("invalid zip code: {}", updated_seller.address.zip_code),
+        )
+    })?;
+    let (opens, closes) = BusinessHours::to_columns(updated_seller.business_hours);
+
+    let mut connection = get_connection(&pool)?;
+    let mut query = diesel::update(sellers)
+        .filter(id.eq(seller_id))
+        .filter(deleted_at.is_null())
+        .set((
+            name.eq(updated_seller.name),
+            phone.eq(updated_seller.phone),
+            email.eq(updated_seller.email),
+            addr_street.eq(updated_seller.address.street),
+            addr_district.eq(updated_seller.address.district),
+            addr_city.eq(updated_seller.address.city),
+            addr_state.eq(updated_seller.address.state),
+            addr_zip_code.eq(zip_code),
+            start_hour.eq(opens),
+            end_hour.eq(closes),
+            updated_at.eq(Utc::now()),
+            updated_by.eq(principal.audit_name()),
+        ))
+        .into_boxed();
+    if !principal.has_role(Role::Admin) {
+        query = query.filter(created_by.eq(principal.subject.clone()));
+    }
+    let update_count = query.execute(&mut connection);
+
+    match update_count {
+        Ok(0) => unmodified(&mut connection, seller_id, principal),
+        Ok(count) => Ok(count),
+        Err(err) => {
+            error!("Unable to update sellers, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+pub fn delete_seller(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    seller_id: Uuid,
+    principal: &Principal,
+) -> Result<usize, AppError> {
+    info!("Delete seller with id: {}", seller_id);
+
+    let delete_count = diesel::update(sellers)
+        .filter(id.eq(seller_id))
+        .filter(deleted_at.is_null())
+        .set((
+            deleted_at.eq(Utc::now()),
+            deleted_by.eq(principal.audit_name()),
+        ))
+        .execute(&mut get_connection(&pool)?);
+
+    match delete_count {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            error!("Unable to delete seller, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+pub fn delete_sellers(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    sellers_ids: Vec<Uuid>,
+    principal: &Principal,
+) -> Result<usize, AppError> {
+    info!("Delete sellers with ids: {:?}", sellers_ids);
+
+    let delete_count = diesel::update(sellers)
+        .filter(id.eq_any(sellers_ids))
+        .filter(deleted_at.is_null())
+        .set((
+            deleted_at.eq(Utc::now()),
+            deleted_by.eq(principal.audit_name()),
+        ))
+        .execute(&mut get_connection(&pool)?);
+
+    match delete_count {
+        Ok(count) => {
+            info!("Sellers delete count: {}", count);
+            Ok(count)
+        }
+        Err(err) => {
+            error!("Unable to delete sellers, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+pub fn restore_seller(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    seller_id: Uuid,
+    principal: &Principal,
+) -> Result<usize, AppError> {
+    info!("Restore seller with id: {}", seller_id);
+
+    let restore_count = diesel::update(sellers)
+        .filter(id.eq(seller_id))
+        .filter(deleted_at.is_not_null())
+        .set((
+            deleted_at.eq(None::<DateTime<Utc>>),
+            deleted_by.eq(None::<String>),
+            updated_at.eq(Utc::now()),
+            updated_by.eq(principal.audit_name()),
+        ))
+        .execute(&mut get_connection(&pool)?);
+
+    match restore_count {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            error!("Unable to restore seller, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+/// Permanently removes a seller, which must have been soft deleted first.
+pub fn purge_seller(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    seller_id: Uuid,
+) -> Result<usize, AppError> {
+    info!("Purge seller with id: {}", seller_id);
+
+    let purge_count = diesel::delete(sellers)
+        .filter(id.eq(seller_id))
+        .filter(deleted_at.is_not_null())
+        .execute(&mut get_connection(&pool)?);
+
+    match purge_count {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            error!("Unable to purge seller, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+/// Sellers modify only the seller records they created, so a record left untouched is either
+/// missing (0 rows, a 404) or someone else's (403).
+fn unmodified(
+    connection: &mut PgConnection,
+    seller_id: Uuid,
+    principal: &Principal,
+) -> Result<usize, AppError> {
+    let found: bool = diesel::select(exists(
+        sellers
+            .filter(id.eq(seller_id))
+            .filter(deleted_at.is_null()),
+    ))
+    .get_result(connection)?;
+
+    if found {
+        warn!(
+            "'{}' tried to modify seller {} created by someone else",
+            principal.subject, seller_id
+        );
+        return Err(AppError::Forbidden(String::from(
+            "Only the user who created a seller, or an admin, may modify it",
+        )));
+    }
+
+    Ok(0)
+}
+
+fn to_entity(
+    new_seller: CreateSeller,
+    now: DateTime<Utc>,
+    author: String,
+) -> Result<Seller, AppError> {
+    let zip_code = parse_zip_code(&new_seller.address.zip_code).ok_or_else(|| {
+        AppError::validation(
+            "address.zip_code",
+            format!("invalid zip code: {}", new_seller.address.zip_code),
+        )
+    })?;
+    let (opens, closes) = BusinessHours::to_columns(new_seller.business_hours);
+
+    Ok(Seller {
+        id: Uuid::new_v4(),
+        name: new_seller.name,
+        phone: new_seller.phone,
+        email: new_seller.email,
+        addr_street: new_seller.address.street,
+        addr_district: new_seller.address.district,
+        addr_city: new_seller.address.city,
+        addr_state: new_seller.address.state,
+        addr_zip_code: zip_code,
+        start_hour: opens,
+        end_hour: closes,
+        created_at: now,
+        updated_at: None,
+        deleted_at: None,
+        created_by: author,
+        updated_by: None,
+        deleted_by: None,
+    })
+}
+
+fn get_connection(
+    pool: &Pool<ConnectionManager<PgConnection>>,
+) -> Result<PooledConnection<ConnectionManager<PgConnection>>, AppError> {
+    let connection = checkout(pool, "seller").map_err(|err| {
+        error!("Unable to connect to database, error: {}", err);
+        AppError::from(err)
+    })?;
+
+    Ok(connection)
+}
+
+// https://stackoverflow.com/a/62029781
+fn sort_by_column<U>(
+    query: BoxedQuery<'static, Pg>,
+    column: U,
+    sort_dir: Option<String>,
+) -> BoxedQuery<'static, Pg>
+where
+    U: 'static + Send + ExpressionMethods + QueryFragment<Pg> + AppearsOnTable<sellers::table>,
+{
+    match sort_dir.as_deref() {
+        Some("asc") => query.order_by(column.asc()),
+        Some("desc") => query.order_by(column.desc()),
+        _ => query,
+    }
+}
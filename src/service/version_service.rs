@@ -0,0 +1,410 @@
+use chrono::{DateTime, Utc};
+
+use crate::auth::principal::Principal;
+use crate::database::database::checkout;
+use crate::error::app_error::AppError;
+use crate::models::version_models::{CreateVersion, UpdateVersion, Version};
+use crate::schema::models;
+use crate::schema::versions::{self, dsl::*, BoxedQuery};
+use crate::service::model_service::live_model_ids;
+use crate::utils::version_columns::{get_column, VersionColumn};
+
+use diesel::dsl::count_star;
+use diesel::pg::Pg;
+use diesel::query_builder::QueryFragment;
+use diesel::r2d2::{ConnectionManager, Pool, PooledConnection};
+use diesel::sql_types;
+use diesel::{
+    AppearsOnTable, ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl, SelectableHelper,
+};
+use log::{error, info, warn};
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[allow(clippy::too_many_arguments)]
+pub fn get_versions(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    parent_model_id: Option<Uuid>,
+    offset: u32,
+    limit: u32,
+    sort_by: String,
+    sort_order: String,
+    filter_by: String,
+    filter_term: String,
+    include_deleted: bool,
+) -> Result<Vec<Version>, AppError> {
+    info!(
+        "Get all versions of model '{:?}' starting at '{}', limited to '{}', sort by '{}' order '{}', filter by '{}' term '{}', include deleted '{}'",
+        parent_model_id, offset, limit, sort_by, sort_order, filter_by, filter_term, include_deleted
+    );
+
+    let mut query = versions::table
+        .into_boxed()
+        .limit(limit as i64)
+        .offset(offset as i64);
+
+    if !include_deleted {
+        query = query
+            .filter(deleted_at.is_null())
+            .filter(model_id.eq_any(live_model_ids()));
+    }
+
+    if let Some(parent_model_id) = parent_model_id {
+        query = query.filter(model_id.eq(parent_model_id));
+    }
+
+    let sort_column: VersionColumn = get_column(sort_by.as_str());
+    query = match sort_column {
+        VersionColumn::Integer(column) => sort_by_column(query, column, Some(sort_order)),
+        VersionColumn::Text(column) => sort_by_column(query, column, Some(sort_order)),
+        VersionColumn::NullableText(column) => sort_by_column(query, column, Some(sort_order)),
+        VersionColumn::Uuid(column) => sort_by_column(query, column, Some(sort_order)),
+    };
+
+    if !filter_by.is_empty() && !filter_term.is_empty() {
+        let filter_column: VersionColumn = get_column(filter_by.as_str());
+        query = match filter_column {
+            VersionColumn::Integer(column) => query.filter(column.eq(filter_term.parse::<i32>()?)),
+            VersionColumn::Text(column) => query.filter(column.eq(filter_term)),
+            VersionColumn::NullableText(column) => query.filter(column.eq(filter_term)),
+            VersionColumn::Uuid(column) => query.filter(column.eq(filter_term.parse::<Uuid>()?)),
+        };
+    }
+
+    let version_list = query.load(&mut get_connection(&pool)?);
+
+    match version_list {
+        Ok(version_list) => Ok(version_list),
+        Err(err) => {
+            error!("Unable to retrieve versions, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+pub fn get_version(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    version_id: Uuid,
+    include_deleted: bool,
+) -> Result<Version, AppError> {
+    info!(
+        "Get version with id: {}, include deleted '{}'",
+        version_id, include_deleted
+    );
+
+    let mut query = versions::table
+        .select(Version::as_select())
+        .filter(id.eq(version_id))
+        .into_boxed();
+
+    if !include_deleted {
+        query = query
+            .filter(deleted_at.is_null())
+            .filter(model_id.eq_any(live_model_ids()));
+    }
+
+    let result = query.first(&mut get_connection(&pool)?);
+
+    match result {
+        Ok(result) => Ok(result),
+        Err(err) => {
+            error!("Unable to retrieve version, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+pub fn create_version(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    create_version: CreateVersion,
+    principal: &Principal,
+) -> Result<Version, AppError> {
+    info!("Create version: {:?}", create_version);
+
+    let mut connection = get_connection(&pool)?;
+    ensure_models_exist(&mut connection, vec![create_version.model_id])?;
+
+    let new_version: Version = Version {
+        id: Uuid::new_v4(),
+        model_id: create_version.model_id,
+        name: create_version.name,
+        engine: create_version.engine,
+        transmission: create_version.transmission,
+        year: create_version.year,
+        body: create_version.body,
+        doors: create_version.doors,
+        created_at: Utc::now(),
+        updated_at: None,
+        deleted_at: None,
+        created_by: principal.audit_name(),
+        updated_by: None,
+        deleted_by: None,
+    };
+
+    let created_version = diesel::insert_into(versions)
+        .values(&new_version)
+        .returning(Version::as_returning())
+        .get_result(&mut connection);
+
+    match created_version {
+        Ok(version) => Ok(version),
+        Err(err) => {
+            error!("Unable to create version, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+pub fn create_versions(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    new_versions: Vec<CreateVersion>,
+    principal: &Principal,
+) -> Result<Vec<Version>, AppError> {
+    info!("Create versions: {:?}", new_versions);
+
+    if new_versions.is_empty() {
+        warn!("No versions to create");
+        return Err(AppError::validation(
+            "body",
+            "must contain at least one version",
+        ));
+    }
+
+    let mut connection = get_connection(&pool)?;
+    ensure_models_exist(
+        &mut connection,
+        new_versions
+            .iter()
+            .map(|new_version| new_version.model_id)
+            .collect(),
+    )?;
+
+    let mut version_entities: Vec<Version> = Vec::new();
+    let now: DateTime<Utc> = Utc::now();
+    let default_created_by = principal.audit_name();
+
+    for new_version in new_versions {
+        version_entities.push(Version {
+            id: Uuid::new_v4(),
+            model_id: new_version.model_id,
+            name: new_version.name,
+            engine: new_version.engine,
+            transmission: new_version.transmission,
+            year: new_version.year,
+            body: new_version.body,
+            doors: new_version.doors,
+            created_at: now,
+            updated_at: None,
+            deleted_at: None,
+            created_by: default_created_by.clone(),
+            updated_by: None,
+            deleted_by: None,
+        });
+    }
+
+    let result = diesel::insert_into(versions)
+        .values(&version_entities)
+        .returning(Version::as_returning())
+        .get_results(&mut connection);
+
+    match result {
+        Ok(created) => Ok(created),
+        Err(err) => {
+            error!("Unable to create versions, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+pub fn update_version(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    version_id: Uuid,
+    updated_version: UpdateVersion,
+    principal: &Principal,
+) -> Result<usize, AppError> {
+    info!("Update version {} to {:?}", version_id, updated_version);
+
+    let mut connection = get_connection(&pool)?;
+    if let Some(new_model_id) = updated_version.model_id {
+        ensure_models_exist(&mut connection, vec![new_model_id])?;
+    }
+
+    let update_count = diesel::update(versions)
+        .filter(id.eq(version_id))
+        .filter(deleted_at.is_null())
+        .set((
+            updated_version,
+            updated_at.eq(Utc::now()),
+            updated_by.eq(principal.audit_name()),
+        ))
+        .execute(&mut connection);
+
+    match update_count {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            error!("Unable to update versions, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+pub fn delete_version(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    version_id: Uuid,
+    principal: &Principal,
+) -> Result<usize, AppError> {
+    info!("Delete version with id: {}", version_id);
+
+    let delete_count = diesel::update(versions)
+        .filter(id.eq(version_id))
+        .filter(deleted_at.is_null())
+        .set((
+            deleted_at.eq(Utc::now()),
+            deleted_by.eq(principal.audit_name()),
+        ))
+        .execute(&mut get_connection(&pool)?);
+
+    match delete_count {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            error!("Unable to delete version, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+pub fn delete_versions(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    versions_ids: Vec<Uuid>,
+    principal: &Principal,
+) -> Result<usize, AppError> {
+    info!("Delete versions with ids: {:?}", versions_ids);
+
+    let delete_count = diesel::update(versions)
+        .filter(id.eq_any(versions_ids))
+        .filter(deleted_at.is_null())
+        .set((
+            deleted_at.eq(Utc::now()),
+            deleted_by.eq(principal.audit_name()),
+        ))
+        .execute(&mut get_connection(&pool)?);
+
+    match delete_count {
+        Ok(count) => {
+            info!("Versions delete count: {}", count);
+            Ok(count)
+        }
+        Err(err) => {
+            error!("Unable to delete versions, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+pub fn restore_version(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    version_id: Uuid,
+    principal: &Principal,
+) -> Result<usize, AppError> {
+    info!("Restore version with id: {}", version_id);
+
+    let restore_count = diesel::update(versions)
+        .filter(id.eq(version_id))
+        .filter(deleted_at.is_not_null())
+        .set((
+            deleted_at.eq(None::<DateTime<Utc>>),
+            deleted_by.eq(None::<String>),
+            updated_at.eq(Utc::now()),
+            updated_by.eq(principal.audit_name()),
+        ))
+        .execute(&mut get_connection(&pool)?);
+
+    match restore_count {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            error!("Unable to restore version, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+/// Permanently removes a version, which must have been soft deleted first.
+pub fn purge_version(
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+    version_id: Uuid,
+) -> Result<usize, AppError> {
+    info!("Purge version with id: {}", version_id);
+
+    let purge_count = diesel::delete(versions)
+        .filter(id.eq(version_id))
+        .filter(deleted_at.is_not_null())
+        .execute(&mut get_connection(&pool)?);
+
+    match purge_count {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            error!("Unable to purge version, error: {}", err);
+            Err(err.into())
+        }
+    }
+}
+
+/// Ids of the versions that are not soft deleted, nor is their model or brand.
+pub fn live_version_ids() -> BoxedQuery<'static, Pg, sql_types::Uuid> {
+    versions::table
+        .select(id)
+        .filter(deleted_at.is_null())
+        .filter(model_id.eq_any(live_model_ids()))
+        .into_boxed()
+}
+
+fn ensure_models_exist(
+    connection: &mut PgConnection,
+    mut models_ids: Vec<Uuid>,
+) -> Result<(), AppError> {
+    models_ids.sort();
+    models_ids.dedup();
+
+    let found: i64 = models::table
+        .filter(models::id.eq_any(&models_ids))
+        .filter(models::id.eq_any(live_model_ids()))
+        .select(count_star())
+        .get_result(connection)?;
+
+    if found as usize != models_ids.len() {
+        warn!("Unknown model in: {:?}", models_ids);
+        return Err(AppError::validation(
+            "model_id",
+            format!("unknown model in: {:?}", models_ids),
+        ));
+    }
+
+    Ok(())
+}
+
+fn get_connection(
+    pool: &Pool<ConnectionManager<PgConnection>>,
+) -> Result<PooledConnection<ConnectionManager<PgConnection>>, AppError> {
+    let connection = checkout(pool, "version").map_err(|err| {
+        error!("Unable to connect to database, error: {}", err);
+        AppError::from(err)
+    })?;
+
+    Ok(connection)
+}
+
+// https://stackoverflow.com/a/62029781
+fn sort_by_column<U>(
+    query: BoxedQuery<'static, Pg>,
+    column: U,
+    sort_dir: Option<String>,
+) -> BoxedQuery<'static, Pg>
+where
+    U: 'static + Send + ExpressionMethods + QueryFragment<Pg> + AppearsOnTable<versions::table>,
+{
+    match sort_dir.as_deref() {
+        Some("asc") => query.order_by(column.asc()),
+        Some("desc") => query.order_by(column.desc()),
+        _ => query,
+    }
+}
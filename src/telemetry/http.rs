@@ -0,0 +1,50 @@
+use std::time::Instant;
+
+use axum::extract::{MatchedPath, Request};
+use axum::middleware::Next;
+use axum::response::Response;
+use log::info;
+use tracing::field::Empty;
+use tracing::{info_span, Instrument};
+
+use crate::utils::request_id::RequestId;
+
+/// Wraps each request in a span named after its route template and tagged with the request id,
+/// recording status and latency and logging them once the response is ready.
+pub async fn trace_requests(request: Request, next: Next) -> Response {
+    let started = Instant::now();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| String::from("unmatched"));
+    let request_id = request
+        .extensions()
+        .get::<RequestId>()
+        .map(|id| id.0.clone())
+        .unwrap_or_default();
+    let span = info_span!(
+        "request",
+        otel.name = format!("{} {}", request.method(), route),
+        request_id = %request_id,
+        method = %request.method(),
+        route = %route,
+        query = request.uri().query().unwrap_or(""),
+        status = Empty,
+        latency_ms = Empty,
+    );
+
+    let response = next.run(request).instrument(span.clone()).await;
+
+    let latency_ms = started.elapsed().as_millis() as u64;
+    span.record("status", response.status().as_u16());
+    span.record("latency_ms", latency_ms);
+    span.in_scope(|| {
+        info!(
+            "Responded with {} in {} ms",
+            response.status().as_u16(),
+            latency_ms
+        )
+    });
+    response
+}
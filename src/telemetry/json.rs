@@ -0,0 +1,260 @@
+use std::fmt;
+
+use chrono::{SecondsFormat, Utc};
+use serde_json::{Map, Value};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Subscriber};
+use tracing_log::NormalizeEvent;
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// Fields of the `request` span that every record logged while serving it carries.
+#[derive(Default)]
+struct RequestFields {
+    request_id: Option<String>,
+    route: Option<String>,
+    latency_ms: Option<u64>,
+}
+
+impl RequestFields {
+    fn write(&self, record: &mut Map<String, Value>) {
+        if let Some(request_id) = &self.request_id {
+            record.insert(String::from("request_id"), Value::from(request_id.as_str()));
+        }
+        if let Some(route) = &self.route {
+            record.insert(String::from("route"), Value::from(route.as_str()));
+        }
+        if let Some(latency_ms) = self.latency_ms {
+            record.insert(String::from("latency_ms"), Value::from(latency_ms));
+        }
+    }
+}
+
+impl Visit for RequestFields {
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        if field.name() == "latency_ms" {
+            self.latency_ms = Some(value);
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "request_id" => self.request_id = Some(value.to_string()),
+            "route" => self.route = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.record_str(field, &format!("{:?}", value));
+    }
+}
+
+/// Keeps the request fields of `request` spans in their extensions, for `JsonFormat` to read.
+pub struct RequestFieldsLayer;
+
+impl<S> Layer<S> for RequestFieldsLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if attrs.metadata().name() != "request" {
+            return;
+        }
+        if let Some(span) = ctx.span(id) {
+            let mut fields = RequestFields::default();
+            attrs.record(&mut fields);
+            span.extensions_mut().insert(fields);
+        }
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(fields) = span.extensions_mut().get_mut::<RequestFields>() {
+                values.record(fields);
+            }
+        }
+    }
+}
+
+/// Event fields, except the `log.*` ones added to records bridged from the `log` crate.
+struct EventFields<'a>(&'a mut Map<String, Value>);
+
+impl Visit for EventFields<'_> {
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.insert(field, Value::from(format!("{:?}", value)));
+    }
+}
+
+impl EventFields<'_> {
+    fn insert(&mut self, field: &Field, value: Value) {
+        if !field.name().starts_with("log.") {
+            self.0.insert(field.name().to_string(), value);
+        }
+    }
+}
+
+/// One JSON object per line with `timestamp`, `level`, `target`, `message`, the other event
+/// fields and, inside a request, its `request_id`, `route` and, once known, `latency_ms`.
+pub struct JsonFormat;
+
+impl<S, N> FormatEvent<S, N> for JsonFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let normalized = event.normalized_metadata();
+        let metadata = normalized.as_ref().unwrap_or_else(|| event.metadata());
+
+        let mut record = Map::new();
+        record.insert(
+            String::from("timestamp"),
+            Value::from(Utc::now().to_rfc3339_opts(SecondsFormat::Micros, true)),
+        );
+        record.insert(
+            String::from("level"),
+            Value::from(metadata.level().as_str()),
+        );
+        record.insert(String::from("target"), Value::from(metadata.target()));
+
+        if let Some(scope) = ctx.event_scope() {
+            for span in scope.from_root() {
+                if let Some(fields) = span.extensions().get::<RequestFields>() {
+                    fields.write(&mut record);
+                }
+            }
+        }
+        event.record(&mut EventFields(&mut record));
+
+        writeln!(writer, "{}", Value::Object(record))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+    use std::sync::{Arc, Mutex};
+
+    use tracing::field::Empty;
+    use tracing::{error, info_span};
+    use tracing_subscriber::fmt::MakeWriter;
+    use tracing_subscriber::layer::SubscriberExt;
+    use tracing_subscriber::{fmt, Registry};
+
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for Buffer {
+        type Writer = Buffer;
+
+        fn make_writer(&'a self) -> Buffer {
+            self.clone()
+        }
+    }
+
+    fn log_lines(emit: impl FnOnce()) -> Vec<Value> {
+        let buffer = Buffer::default();
+        let subscriber = Registry::default().with(RequestFieldsLayer).with(
+            fmt::layer()
+                .event_format(JsonFormat)
+                .with_writer(buffer.clone()),
+        );
+        tracing::subscriber::with_default(subscriber, emit);
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn records_in_a_request_carry_its_fields() {
+        // Given 'an error logged by a service while serving a request'
+        let lines = log_lines(|| {
+            let span = info_span!(
+                "request",
+                request_id = "8d1f6c2e",
+                route = "/v1/post/bulk",
+                latency_ms = Empty
+            );
+            span.in_scope(|| {
+                let service = info_span!("create_posts", rows = Empty);
+                service.in_scope(|| {
+                    error!(target: "showroom_api::service::post_service", "Unable to create posts")
+                });
+            });
+            span.record("latency_ms", 12_u64);
+            span.in_scope(|| error!(target: "showroom_api::telemetry::http", "Responded"));
+        });
+
+        // When 'the records are read back'
+        let (failure, response) = (&lines[0], &lines[1]);
+
+        // Then 'both should carry the request fields, latency once recorded'
+        assert_eq!(failure["level"], "ERROR");
+        assert_eq!(failure["target"], "showroom_api::service::post_service");
+        assert_eq!(failure["message"], "Unable to create posts");
+        assert_eq!(failure["request_id"], "8d1f6c2e");
+        assert_eq!(failure["route"], "/v1/post/bulk");
+        assert!(failure.get("latency_ms").is_none());
+        assert_eq!(response["request_id"], "8d1f6c2e");
+        assert_eq!(response["latency_ms"], 12);
+    }
+
+    #[test]
+    fn records_outside_a_request_have_no_request_fields() {
+        // Given 'a record logged at startup'
+        let lines = log_lines(|| error!(target: "showroom_api", "Unable to bind"));
+
+        // When 'the record is read back'
+        let record = &lines[0];
+
+        // Then 'it should have no request fields'
+        assert_eq!(record["message"], "Unable to bind");
+        assert!(record.get("request_id").is_none());
+        assert!(record.get("route").is_none());
+    }
+}
@@ -0,0 +1,53 @@
+use std::error::Error;
+use std::io::{self, IsTerminal};
+
+use opentelemetry_sdk::trace::TracerProvider;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, EnvFilter, Layer, Registry};
+
+use crate::config::config::{LogConfig, LogFormat, TracingConfig};
+use crate::telemetry::json::{JsonFormat, RequestFieldsLayer};
+use crate::telemetry::otlp;
+
+/// Installs the global subscriber, `log` records included, writing text or JSON lines to stderr
+/// and exporting spans over OTLP when tracing is enabled.
+///
+/// The returned provider, if any, must be shut down on exit to flush the last spans.
+pub fn init_logger(
+    log: &LogConfig,
+    tracing: &TracingConfig,
+) -> Result<Option<TracerProvider>, Box<dyn Error>> {
+    let filter = EnvFilter::try_new(&log.level)?;
+
+    let output = match log.format {
+        LogFormat::Text => fmt::layer()
+            .with_ansi(io::stderr().is_terminal())
+            .with_writer(io::stderr)
+            .boxed(),
+        LogFormat::Json => RequestFieldsLayer
+            .and_then(
+                fmt::layer()
+                    .event_format(JsonFormat)
+                    .with_writer(io::stderr),
+            )
+            .boxed(),
+    };
+
+    let provider = if tracing.enabled {
+        Some(otlp::tracer_provider(tracing)?)
+    } else {
+        None
+    };
+    let export = provider.as_ref().map(|provider| {
+        tracing_opentelemetry::layer().with_tracer(otlp::tracer(provider, tracing))
+    });
+
+    Registry::default()
+        .with(filter)
+        .with(output)
+        .with(export)
+        .try_init()?;
+
+    Ok(provider)
+}
@@ -0,0 +1,4 @@
+pub mod http;
+pub mod json;
+pub mod logging;
+pub mod otlp;
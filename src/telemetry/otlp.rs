@@ -0,0 +1,32 @@
+use std::error::Error;
+
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::trace::{Sampler, Tracer, TracerProvider};
+use opentelemetry_sdk::{runtime, Resource};
+
+use crate::config::config::TracingConfig;
+
+/// Batches spans to the OTLP collector, sampling root spans at `sample_ratio`.
+pub fn tracer_provider(config: &TracingConfig) -> Result<TracerProvider, Box<dyn Error>> {
+    let exporter = SpanExporter::builder()
+        .with_tonic()
+        .with_endpoint(&config.otlp_endpoint)
+        .build()?;
+
+    Ok(TracerProvider::builder()
+        .with_batch_exporter(exporter, runtime::Tokio)
+        .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+            config.sample_ratio,
+        ))))
+        .with_resource(Resource::new(vec![KeyValue::new(
+            "service.name",
+            config.service_name.clone(),
+        )]))
+        .build())
+}
+
+pub fn tracer(provider: &TracerProvider, config: &TracingConfig) -> Tracer {
+    provider.tracer(config.service_name.clone())
+}
@@ -0,0 +1,46 @@
+use log::error;
+use tokio::task;
+use tracing::Span;
+
+use crate::error::app_error::AppError;
+
+/// Runs blocking database work on tokio's blocking thread pool, so a slow query or a wait for a
+/// pooled connection never stalls the async workers serving other requests.
+///
+/// The work runs inside the caller's span, so its logs and spans stay tied to the request.
+pub async fn run_blocking<F, T>(work: F) -> Result<T, AppError>
+where
+    F: FnOnce() -> Result<T, AppError> + Send + 'static,
+    T: Send + 'static,
+{
+    let span = Span::current();
+    task::spawn_blocking(move || span.in_scope(work))
+        .await
+        .unwrap_or_else(|err| {
+            error!("Blocking task failed, error: {}", err);
+            Err(AppError::Internal(String::from("Internal server error")))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn work_result_is_returned() {
+        // When 'the work succeeds'
+        let result = run_blocking(|| Ok(42)).await;
+
+        // Then 'its result should be returned'
+        assert_eq!(result, Ok(42));
+    }
+
+    #[tokio::test]
+    async fn panicking_work_is_an_internal_error() {
+        // When 'the work panics'
+        let result: Result<(), AppError> = run_blocking(|| panic!("boom")).await;
+
+        // Then 'the panic should be reported as an internal error'
+        assert!(matches!(result, Err(AppError::Internal(_))));
+    }
+}
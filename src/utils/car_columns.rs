@@ -0,0 +1,46 @@
+use diesel::helper_types::{InnerJoin, InnerJoinQuerySource};
+use diesel::{
+    pg::Pg,
+    sql_types::{Bool, Integer, Nullable, Text, Uuid},
+    BoxableExpression,
+};
+use log::info;
+
+use crate::schema::{brands, cars, models, versions};
+
+/// The `cars` table joined with its version, model and brand.
+pub type CarQuerySource = InnerJoinQuerySource<
+    cars::table,
+    InnerJoin<versions::table, InnerJoin<models::table, brands::table>>,
+>;
+
+pub enum CarColumn {
+    Integer(Box<dyn BoxableExpression<CarQuerySource, Pg, SqlType = Integer>>),
+    Text(Box<dyn BoxableExpression<CarQuerySource, Pg, SqlType = Text>>),
+    NullableText(Box<dyn BoxableExpression<CarQuerySource, Pg, SqlType = Nullable<Text>>>),
+    Bool(Box<dyn BoxableExpression<CarQuerySource, Pg, SqlType = Bool>>),
+    Uuid(Box<dyn BoxableExpression<CarQuerySource, Pg, SqlType = Uuid>>),
+}
+
+pub fn get_column(sort_by: &str) -> CarColumn {
+    match sort_by {
+        "version_id" => CarColumn::Uuid(Box::new(cars::version_id)),
+        "mileage" => CarColumn::Integer(Box::new(cars::mileage)),
+        "color" => CarColumn::Text(Box::new(cars::color)),
+        "armored" => CarColumn::Bool(Box::new(cars::armored)),
+        "owner" => CarColumn::Text(Box::new(cars::owner)),
+        "created_by" => CarColumn::Text(Box::new(cars::created_by)),
+        "updated_by" => CarColumn::NullableText(Box::new(cars::updated_by)),
+        "brand" => CarColumn::Text(Box::new(brands::name)),
+        "model" => CarColumn::Text(Box::new(models::name)),
+        "version" => CarColumn::Text(Box::new(versions::name)),
+        "year" => CarColumn::Integer(Box::new(versions::year)),
+        _ => {
+            info!(
+                "Unknown column name: '{}', defaulting to 'mileage'",
+                sort_by
+            );
+            CarColumn::Integer(Box::new(cars::mileage))
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use validator::ValidationError;
+
+pub fn validate_phone(phone: &str) -> Result<(), ValidationError> {
+    if is_valid_phone(phone) {
+        Ok(())
+    } else {
+        Err(ValidationError::new("phone").with_message("must be a valid phone number".into()))
+    }
+}
+
+pub fn validate_zip_code(zip_code: &str) -> Result<(), ValidationError> {
+    match parse_zip_code(zip_code) {
+        Some(_) => Ok(()),
+        None => {
+            Err(ValidationError::new("zip_code")
+                .with_message("must have the format 00000-000".into()))
+        }
+    }
+}
+
+/// Accepts digits with an optional leading `+` and the usual separators, 10 to 13 digits long.
+pub fn is_valid_phone(phone: &str) -> bool {
+    let phone = phone.trim();
+    let number = phone.strip_prefix('+').unwrap_or(phone);
+
+    if !number
+        .chars()
+        .all(|c| c.is_ascii_digit() || matches!(c, ' ' | '-' | '(' | ')'))
+    {
+        return false;
+    }
+
+    let digits = number.chars().filter(char::is_ascii_digit).count();
+    (10..=13).contains(&digits)
+}
+
+/// Parses a brazilian zip code (CEP), either `00000-000` or `00000000`.
+pub fn parse_zip_code(zip_code: &str) -> Option<i32> {
+    let digits: String = match zip_code.split_once('-') {
+        Some((prefix, suffix)) if prefix.len() == 5 && suffix.len() == 3 => {
+            format!("{}{}", prefix, suffix)
+        }
+        Some(_) => return None,
+        None => zip_code.to_string(),
+    };
+
+    if digits.len() != 8 || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    digits.parse::<i32>().ok()
+}
+
+pub fn format_zip_code(zip_code: i32) -> String {
+    format!("{:05}-{:03}", zip_code / 1000, zip_code % 1000)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_phones() {
+        // Given 'phone numbers with and without separators'
+        let phones = ["+55 (11) 91234-5678", "11912345678", "1133334444"];
+
+        // Then 'all of them should be valid'
+        for phone in phones {
+            assert!(is_valid_phone(phone), "{}", phone);
+        }
+    }
+
+    #[test]
+    fn invalid_phones() {
+        // Given 'phone numbers too short, too long or with letters'
+        let phones = ["12345", "+55 11 91234-5678 999", "11 9123A-5678", ""];
+
+        // Then 'none of them should be valid'
+        for phone in phones {
+            assert!(!is_valid_phone(phone), "{}", phone);
+        }
+    }
+
+    #[test]
+    fn zip_code_round_trip() {
+        // Given 'a zip code with a leading zero'
+        let zip_code = "01310-100";
+
+        // When 'the application parses and formats it back'
+        let parsed = parse_zip_code(zip_code);
+
+        // Then 'the leading zero should be kept'
+        assert_eq!(parsed, Some(1310100));
+        assert_eq!(format_zip_code(parsed.unwrap()), zip_code);
+    }
+
+    #[test]
+    fn invalid_zip_codes() {
+        assert_eq!(parse_zip_code("0131-0100"), None);
+        assert_eq!(parse_zip_code("1310100"), None);
+        assert_eq!(parse_zip_code("01310-10a"), None);
+    }
+}
@@ -0,0 +1,51 @@
+use diesel::helper_types::{InnerJoin, InnerJoinQuerySource};
+use diesel::{
+    pg::Pg,
+    sql_types::{Bool, Integer, Text, Uuid},
+    BoxableExpression,
+};
+use log::info;
+
+use crate::schema::{brands, cars, listings, models, sellers, versions};
+
+/// The `listings` table joined with its car, the car's catalog entries and the seller.
+pub type ListingQuerySource = InnerJoinQuerySource<
+    InnerJoinQuerySource<
+        listings::table,
+        InnerJoin<cars::table, InnerJoin<versions::table, InnerJoin<models::table, brands::table>>>,
+    >,
+    sellers::table,
+>;
+
+pub enum ListingColumn {
+    Integer(Box<dyn BoxableExpression<ListingQuerySource, Pg, SqlType = Integer>>),
+    Text(Box<dyn BoxableExpression<ListingQuerySource, Pg, SqlType = Text>>),
+    Bool(Box<dyn BoxableExpression<ListingQuerySource, Pg, SqlType = Bool>>),
+    Uuid(Box<dyn BoxableExpression<ListingQuerySource, Pg, SqlType = Uuid>>),
+}
+
+pub fn get_column(sort_by: &str) -> ListingColumn {
+    match sort_by {
+        "brand" => ListingColumn::Text(Box::new(brands::name)),
+        "model" => ListingColumn::Text(Box::new(models::name)),
+        "version" => ListingColumn::Text(Box::new(versions::name)),
+        "engine" => ListingColumn::Text(Box::new(versions::engine)),
+        "transmission" => ListingColumn::Text(Box::new(versions::transmission)),
+        "year" => ListingColumn::Integer(Box::new(versions::year)),
+        "body" => ListingColumn::Text(Box::new(versions::body)),
+        "doors" => ListingColumn::Integer(Box::new(versions::doors)),
+        "mileage" => ListingColumn::Integer(Box::new(cars::mileage)),
+        "color" => ListingColumn::Text(Box::new(cars::color)),
+        "armored" => ListingColumn::Bool(Box::new(cars::armored)),
+        "exchange" => ListingColumn::Bool(Box::new(listings::exchange)),
+        "price" => ListingColumn::Integer(Box::new(listings::price)),
+        "seller_id" => ListingColumn::Uuid(Box::new(listings::seller_id)),
+        "seller" => ListingColumn::Text(Box::new(sellers::name)),
+        "city" => ListingColumn::Text(Box::new(sellers::addr_city)),
+        "state" => ListingColumn::Text(Box::new(sellers::addr_state)),
+        _ => {
+            info!("Unknown column name: '{}', defaulting to 'model'", sort_by);
+            ListingColumn::Text(Box::new(models::name))
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Deserializer};
+
+/// Deserializes a field of a JSON Merge Patch (RFC 7386) that the resource does not allow to be removed.
+///
+/// A missing field deserializes to `None` and is left untouched, while an explicit `null`, which
+/// would remove the member, is rejected instead of being silently ignored.
+pub fn required<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Deserialize, Debug)]
+    struct Patch {
+        #[serde(default, deserialize_with = "required")]
+        price: Option<i64>,
+    }
+
+    #[test]
+    fn missing_fields_are_left_untouched() {
+        // Given 'a patch without the field'
+        let patch: Patch = serde_json::from_str("{}").unwrap();
+
+        // Then 'the field should not be changed'
+        assert_eq!(patch.price, None);
+    }
+
+    #[test]
+    fn present_fields_are_changed() {
+        // Given 'a patch with the field'
+        let patch: Patch = serde_json::from_str(r#"{"price": 10}"#).unwrap();
+
+        // Then 'the field should be changed'
+        assert_eq!(patch.price, Some(10));
+    }
+
+    #[test]
+    fn null_fields_are_rejected() {
+        // Given 'a patch removing the field'
+        let patch = serde_json::from_str::<Patch>(r#"{"price": null}"#);
+
+        // Then 'the patch should be rejected'
+        assert!(patch.is_err());
+    }
+}
@@ -1,2 +1,14 @@
+pub mod blocking;
 pub mod brand_columns;
+pub mod car_columns;
+pub mod contact_validation;
+pub mod listing_columns;
+pub mod merge_patch;
+pub mod model_columns;
 pub mod post_columns;
+pub mod request_id;
+pub mod seller_columns;
+pub mod time_validation;
+pub mod validated_json;
+pub mod vehicle_validation;
+pub mod version_columns;
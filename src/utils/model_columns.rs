@@ -0,0 +1,27 @@
+use diesel::{
+    pg::Pg,
+    sql_types::{Nullable, Text, Uuid},
+    BoxableExpression,
+};
+use log::info;
+
+use crate::schema::models::{self, *};
+
+pub enum ModelColumn {
+    Text(Box<dyn BoxableExpression<models::table, Pg, SqlType = Text>>),
+    NullableText(Box<dyn BoxableExpression<models::table, Pg, SqlType = Nullable<Text>>>),
+    Uuid(Box<dyn BoxableExpression<models::table, Pg, SqlType = Uuid>>),
+}
+
+pub fn get_column(sort_by: &str) -> ModelColumn {
+    match sort_by {
+        "name" => ModelColumn::Text(Box::new(name)),
+        "brand_id" => ModelColumn::Uuid(Box::new(brand_id)),
+        "created_by" => ModelColumn::Text(Box::new(created_by)),
+        "updated_by" => ModelColumn::NullableText(Box::new(updated_by)),
+        _ => {
+            info!("Unknown column name: '{}', defaulting to 'name'", sort_by);
+            ModelColumn::Text(Box::new(name))
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use diesel::{
-    sql_types::{BigInt, Bool, Integer, Text},
+    sql_types::{BigInt, Bool, Integer, Nullable, Text, Timestamptz},
     BoxableExpression,
 };
 use log::info;
@@ -11,6 +11,13 @@ pub enum PostColumn {
     Text(Box<dyn BoxableExpression<posts::table, diesel::pg::Pg, SqlType = Text>>),
     Bool(Box<dyn BoxableExpression<posts::table, diesel::pg::Pg, SqlType = Bool>>),
     BigInteger(Box<dyn BoxableExpression<posts::table, diesel::pg::Pg, SqlType = BigInt>>),
+    NullableText(
+        Box<dyn BoxableExpression<posts::table, diesel::pg::Pg, SqlType = Nullable<Text>>>,
+    ),
+    Timestamp(Box<dyn BoxableExpression<posts::table, diesel::pg::Pg, SqlType = Timestamptz>>),
+    NullableTimestamp(
+        Box<dyn BoxableExpression<posts::table, diesel::pg::Pg, SqlType = Nullable<Timestamptz>>>,
+    ),
 }
 
 pub fn get_column(sort_by: &str) -> PostColumn {
@@ -30,6 +37,10 @@ pub fn get_column(sort_by: &str) -> PostColumn {
         "thumbnail_url" => PostColumn::Text(Box::new(thumbnail_url)),
         "author" => PostColumn::Text(Box::new(author)),
         "published" => PostColumn::Bool(Box::new(published)),
+        "created_at" => PostColumn::Timestamp(Box::new(created_at)),
+        "updated_at" => PostColumn::NullableTimestamp(Box::new(updated_at)),
+        "created_by" => PostColumn::Text(Box::new(created_by)),
+        "updated_by" => PostColumn::NullableText(Box::new(updated_by)),
         _ => {
             info!("Unknown column name: '{}', defaulting to 'model'", sort_by);
             PostColumn::Text(Box::new(model))
@@ -0,0 +1,84 @@
+use axum::extract::Request;
+use axum::http::HeaderValue;
+use axum::middleware::Next;
+use axum::response::Response;
+use uuid::Uuid;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Longest client supplied id kept as is, anything longer is replaced.
+const MAX_REQUEST_ID_LENGTH: usize = 128;
+
+/// The id of the request being served, taken from `X-Request-Id` or generated.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RequestId(pub String);
+
+impl RequestId {
+    /// Keeps a client supplied id made of printable, unspaced ASCII, or generates a UUID.
+    pub fn from_header(value: Option<&HeaderValue>) -> RequestId {
+        value
+            .and_then(|value| value.to_str().ok())
+            .filter(|value| is_valid(value))
+            .map(|value| RequestId(value.to_string()))
+            .unwrap_or_else(|| RequestId(Uuid::new_v4().to_string()))
+    }
+}
+
+fn is_valid(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= MAX_REQUEST_ID_LENGTH
+        && value.bytes().all(|byte| byte.is_ascii_graphic())
+}
+
+/// Middleware settling the request id, available to inner layers as a `RequestId` extension
+/// and in the request header, and echoed in the response header.
+pub async fn request_id(mut request: Request, next: Next) -> Response {
+    let id = RequestId::from_header(request.headers().get(REQUEST_ID_HEADER));
+    let header = HeaderValue::from_str(&id.0).expect("request ids are visible ASCII");
+
+    request
+        .headers_mut()
+        .insert(REQUEST_ID_HEADER, header.clone());
+    request.extensions_mut().insert(id);
+
+    let mut response = next.run(request).await;
+    response.headers_mut().insert(REQUEST_ID_HEADER, header);
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn client_request_id_is_kept() {
+        // Given 'a request id sent by the client'
+        let header = HeaderValue::from_static("checkout-42.retry_1");
+
+        // When 'the request id is settled'
+        let id = RequestId::from_header(Some(&header));
+
+        // Then 'the client id should be used'
+        assert_eq!(id, RequestId(String::from("checkout-42.retry_1")));
+    }
+
+    #[test]
+    fn missing_or_unusable_request_id_is_generated() {
+        // Given 'no header, an empty one, one with spaces and an overly long one'
+        let long = HeaderValue::from_str(&"a".repeat(MAX_REQUEST_ID_LENGTH + 1)).unwrap();
+        let values = [
+            None,
+            Some(HeaderValue::from_static("")),
+            Some(HeaderValue::from_static("two words")),
+            Some(long),
+        ];
+
+        for value in values {
+            // When 'the request id is settled'
+            let id = RequestId::from_header(value.as_ref());
+
+            // Then 'a UUID should be generated'
+            assert!(Uuid::parse_str(&id.0).is_ok(), "{:?} was kept", value);
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use diesel::{
+    pg::Pg,
+    sql_types::{Integer, Nullable, Text},
+    BoxableExpression,
+};
+use log::info;
+
+use crate::schema::sellers::{self, *};
+
+pub enum SellerColumn {
+    Integer(Box<dyn BoxableExpression<sellers::table, Pg, SqlType = Integer>>),
+    Text(Box<dyn BoxableExpression<sellers::table, Pg, SqlType = Text>>),
+    NullableText(Box<dyn BoxableExpression<sellers::table, Pg, SqlType = Nullable<Text>>>),
+}
+
+pub fn get_column(sort_by: &str) -> SellerColumn {
+    match sort_by {
+        "name" => SellerColumn::Text(Box::new(name)),
+        "phone" => SellerColumn::Text(Box::new(phone)),
+        "email" => SellerColumn::Text(Box::new(email)),
+        "addr_district" => SellerColumn::Text(Box::new(addr_district)),
+        "addr_city" => SellerColumn::Text(Box::new(addr_city)),
+        "addr_state" => SellerColumn::Text(Box::new(addr_state)),
+        "addr_zip_code" => SellerColumn::Integer(Box::new(addr_zip_code)),
+        "created_by" => SellerColumn::Text(Box::new(created_by)),
+        "updated_by" => SellerColumn::NullableText(Box::new(updated_by)),
+        _ => {
+            info!("Unknown column name: '{}', defaulting to 'name'", sort_by);
+            SellerColumn::Text(Box::new(name))
+        }
+    }
+}
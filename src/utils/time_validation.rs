@@ -0,0 +1,35 @@
+use chrono::{DateTime, Utc};
+use validator::ValidationError;
+
+/// Expiry dates must lie ahead, something expiring in the past would be dead on arrival.
+pub fn validate_future(at: &DateTime<Utc>) -> Result<(), ValidationError> {
+    if *at > Utc::now() {
+        Ok(())
+    } else {
+        Err(ValidationError::new("future").with_message("must be in the future".into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn tomorrow_is_in_the_future() {
+        // Given 'a date a day from now'
+        let at = Utc::now() + Duration::days(1);
+
+        // Then 'it should be valid'
+        assert!(validate_future(&at).is_ok());
+    }
+
+    #[test]
+    fn past_dates_are_not_in_the_future() {
+        // Given 'a date a second ago'
+        let at = Utc::now() - Duration::seconds(1);
+
+        // Then 'it should be invalid'
+        assert!(validate_future(&at).is_err());
+    }
+}
@@ -0,0 +1,235 @@
+use axum::async_trait;
+use axum::extract::{FromRequest, Request};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::de::DeserializeOwned;
+use validator::{Validate, ValidationError, ValidationErrors, ValidationErrorsKind};
+
+use crate::error::app_error::AppError;
+use crate::error::problem::FieldError;
+
+/// Json extractor that also runs the payload's `Validate` rules.
+///
+/// Rejects with a 422 problem listing every violated field, so handlers only ever see valid payloads.
+/// Bodies are read as `application/json` or any `+json` type, `application/merge-patch+json` for
+/// the PATCH routes included, anything else is a 415.
+pub struct ValidatedJson<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for ValidatedJson<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(payload) = Json::<T>::from_request(request, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+
+        payload
+            .validate()
+            .map_err(|errors| AppError::Validation(field_errors(&errors)).into_response())?;
+
+        Ok(ValidatedJson(payload))
+    }
+}
+
+/// Flattens nested validation errors into one entry per field, e.g. `[1].address.zip_code`.
+pub fn field_errors(errors: &ValidationErrors) -> Vec<FieldError> {
+    let mut field_errors: Vec<FieldError> = Vec::new();
+    collect_field_errors(errors, "", &mut field_errors);
+    field_errors.sort_by(|left, right| left.field.cmp(&right.field));
+    field_errors
+}
+
+fn collect_field_errors(errors: &ValidationErrors, path: &str, field_errors: &mut Vec<FieldError>) {
+    for (field, kind) in errors.errors() {
+        let field_path = match *field {
+            // Lists validated at the top level and struct level rules have no field of their own.
+            "_tmp_validator" | "__all__" if path.is_empty() => String::from("body"),
+            "_tmp_validator" | "__all__" => path.to_string(),
+            field if path.is_empty() => field.to_string(),
+            field => format!("{}.{}", path, field),
+        };
+
+        match kind {
+            ValidationErrorsKind::Field(errors) => {
+                for error in errors {
+                    field_errors.push(FieldError::new(field_path.clone(), describe(error)));
+                }
+            }
+            ValidationErrorsKind::Struct(errors) => {
+                collect_field_errors(errors, &field_path, field_errors)
+            }
+            ValidationErrorsKind::List(items) => {
+                for (index, errors) in items {
+                    let item_path = match field_path.as_str() {
+                        "body" => format!("[{}]", index),
+                        field_path => format!("{}[{}]", field_path, index),
+                    };
+                    collect_field_errors(errors, &item_path, field_errors);
+                }
+            }
+        }
+    }
+}
+
+fn describe(error: &ValidationError) -> String {
+    if let Some(message) = &error.message {
+        return message.to_string();
+    }
+
+    let min = error.params.get("min").map(|min| min.to_string());
+    let max = error.params.get("max").map(|max| max.to_string());
+
+    match (error.code.as_ref(), min, max) {
+        ("length", Some(min), Some(max)) => {
+            format!("must be between {} and {} characters long", min, max)
+        }
+        ("length", Some(min), None) => format!("must be at least {} characters long", min),
+        ("length", None, Some(max)) => format!("must be at most {} characters long", max),
+        ("range", Some(min), Some(max)) => format!("must be between {} and {}", min, max),
+        ("range", Some(min), None) => format!("must be at least {}", min),
+        ("range", None, Some(max)) => format!("must be at most {}", max),
+        ("url", _, _) => String::from("must be a valid URL"),
+        ("email", _, _) => String::from("must be a valid email address"),
+        (code, _, _) => format!("is invalid ({})", code),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::{header, Method, StatusCode};
+    use axum::routing::patch;
+    use axum::Router;
+    use serde::Deserialize;
+    use tower::Service;
+
+    #[derive(Deserialize, Validate)]
+    struct Item {
+        #[validate(length(min = 1, max = 10))]
+        name: String,
+        #[validate(range(min = 0))]
+        price: i64,
+        #[validate(nested)]
+        tag: Tag,
+    }
+
+    #[derive(Deserialize, Validate)]
+    struct Tag {
+        #[validate(url)]
+        url: String,
+    }
+
+    #[test]
+    fn nested_errors_are_flattened() {
+        // Given 'an item with every field invalid'
+        let item = Item {
+            name: String::new(),
+            price: -1,
+            tag: Tag {
+                url: String::from("not a url"),
+            },
+        };
+
+        // When 'the item is validated'
+        let errors = field_errors(&item.validate().unwrap_err());
+
+        // Then 'every field should be reported with its path'
+        assert_eq!(
+            errors,
+            vec![
+                FieldError::new("name", "must be between 1 and 10 characters long"),
+                FieldError::new("price", "must be at least 0"),
+                FieldError::new("tag.url", "must be a valid URL"),
+            ]
+        );
+    }
+
+    #[test]
+    fn list_errors_are_indexed() {
+        // Given 'a list where only the second item is invalid'
+        let items = vec![
+            Item {
+                name: String::from("valid"),
+                price: 1,
+                tag: Tag {
+                    url: String::from("https://example.com"),
+                },
+            },
+            Item {
+                name: String::from("valid"),
+                price: -1,
+                tag: Tag {
+                    url: String::from("https://example.com"),
+                },
+            },
+        ];
+
+        // When 'the list is validated'
+        let errors = field_errors(&items.validate().unwrap_err());
+
+        // Then 'the error should point to the second item'
+        assert_eq!(
+            errors,
+            vec![FieldError::new("[1].price", "must be at least 0")]
+        );
+    }
+
+    async fn patch_status(content_type: &str, body: &str) -> StatusCode {
+        let mut app = Router::new().route(
+            "/item",
+            patch(|ValidatedJson(_): ValidatedJson<Tag>| async { StatusCode::NO_CONTENT }),
+        );
+        let request = Request::builder()
+            .method(Method::PATCH)
+            .uri("/item")
+            .header(header::CONTENT_TYPE, content_type)
+            .body(Body::from(body.to_string()))
+            .unwrap();
+
+        app.call(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn merge_patches_are_accepted() {
+        // Given 'a valid patch sent as a JSON Merge Patch and as plain JSON'
+        let body = r#"{"url": "https://example.com"}"#;
+
+        // When 'the patch is extracted'
+        let merge_patch = patch_status("application/merge-patch+json", body).await;
+        let json = patch_status("application/json", body).await;
+
+        // Then 'both should reach the handler'
+        assert_eq!(merge_patch, StatusCode::NO_CONTENT);
+        assert_eq!(json, StatusCode::NO_CONTENT);
+    }
+
+    #[tokio::test]
+    async fn invalid_merge_patches_are_rejected() {
+        // Given 'an invalid patch sent as a JSON Merge Patch'
+        let body = r#"{"url": "not a url"}"#;
+
+        // When 'the patch is extracted'
+        let status = patch_status("application/merge-patch+json", body).await;
+
+        // Then 'it should be rejected as invalid'
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    async fn non_json_bodies_are_unsupported() {
+        // Given 'a patch sent as plain text'
+        let body = r#"{"url": "https://example.com"}"#;
+
+        // When 'the patch is extracted'
+        let status = patch_status("text/plain", body).await;
+
+        // Then 'it should be an unsupported media type'
+        assert_eq!(status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    }
+}
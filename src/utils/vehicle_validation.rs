@@ -0,0 +1,42 @@
+use chrono::{Datelike, Utc};
+use validator::ValidationError;
+
+const FIRST_MODEL_YEAR: i32 = 1900;
+
+/// Model years run from 1900 up to next year, as next year's models go on sale early.
+pub fn validate_model_year(year: i32) -> Result<(), ValidationError> {
+    let last_model_year = Utc::now().year() + 1;
+
+    if (FIRST_MODEL_YEAR..=last_model_year).contains(&year) {
+        Ok(())
+    } else {
+        Err(ValidationError::new("range").with_message(
+            format!(
+                "must be between {} and {}",
+                FIRST_MODEL_YEAR, last_model_year
+            )
+            .into(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_year_is_a_valid_model_year() {
+        // Given 'next year'
+        let year = Utc::now().year() + 1;
+
+        // Then 'it should be a valid model year'
+        assert!(validate_model_year(year).is_ok());
+    }
+
+    #[test]
+    fn far_years_are_invalid_model_years() {
+        assert!(validate_model_year(1).is_err());
+        assert!(validate_model_year(Utc::now().year() + 2).is_err());
+        assert!(validate_model_year(9999).is_err());
+    }
+}
@@ -0,0 +1,33 @@
+use diesel::{
+    pg::Pg,
+    sql_types::{Integer, Nullable, Text, Uuid},
+    BoxableExpression,
+};
+use log::info;
+
+use crate::schema::versions::{self, *};
+
+pub enum VersionColumn {
+    Integer(Box<dyn BoxableExpression<versions::table, Pg, SqlType = Integer>>),
+    Text(Box<dyn BoxableExpression<versions::table, Pg, SqlType = Text>>),
+    NullableText(Box<dyn BoxableExpression<versions::table, Pg, SqlType = Nullable<Text>>>),
+    Uuid(Box<dyn BoxableExpression<versions::table, Pg, SqlType = Uuid>>),
+}
+
+pub fn get_column(sort_by: &str) -> VersionColumn {
+    match sort_by {
+        "name" => VersionColumn::Text(Box::new(name)),
+        "model_id" => VersionColumn::Uuid(Box::new(model_id)),
+        "engine" => VersionColumn::Text(Box::new(engine)),
+        "transmission" => VersionColumn::Text(Box::new(transmission)),
+        "year" => VersionColumn::Integer(Box::new(year)),
+        "body" => VersionColumn::Text(Box::new(body)),
+        "doors" => VersionColumn::Integer(Box::new(doors)),
+        "created_by" => VersionColumn::Text(Box::new(created_by)),
+        "updated_by" => VersionColumn::NullableText(Box::new(updated_by)),
+        _ => {
+            info!("Unknown column name: '{}', defaulting to 'name'", sort_by);
+            VersionColumn::Text(Box::new(name))
+        }
+    }
+}